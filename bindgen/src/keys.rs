@@ -0,0 +1,131 @@
+use std::{collections::HashSet, fs};
+
+use fluent_localization_loader::{fold_displayable, load_resources_from_folder, DEFAULT_DIR};
+use fluent_syntax::ast::Entry;
+use quote::quote;
+use syn::{
+    punctuated::Punctuated, Attribute, Data, DeriveInput, Expr, ExprLit, Lit, LitStr, Meta, Token,
+};
+
+use crate::root_dirs;
+
+/// Check the keys in the `#[localization_key = "..."]` attributes of a type against the keys of the default resources,
+/// of the directories in `#[localization_dirs(...)]` or the default one
+pub fn derive(input: DeriveInput) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let attributes: Vec<&Attribute> = match &input.data {
+        Data::Struct(data) => data.fields.iter().flat_map(|field| &field.attrs).collect(),
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .flat_map(|variant| {
+                variant
+                    .attrs
+                    .iter()
+                    .chain(variant.fields.iter().flat_map(|field| &field.attrs))
+            })
+            .collect(),
+        Data::Union(data) => data
+            .fields
+            .named
+            .iter()
+            .flat_map(|field| &field.attrs)
+            .collect(),
+    };
+    let keys = attributes
+        .into_iter()
+        .filter(|attribute| attribute.path().is_ident("localization_key"))
+        .map(key_of)
+        .collect::<Result<Vec<LitStr>, syn::Error>>()?;
+
+    let mut dirs = Vec::new();
+    for attribute in input
+        .attrs
+        .iter()
+        .filter(|attribute| attribute.path().is_ident("localization_dirs"))
+    {
+        dirs.extend(attribute.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?);
+    }
+
+    // The same directories the bindings read, with the files of every one of them
+    let mut resources = Vec::new();
+    for root_dir in root_dirs(&dirs) {
+        let base_dir = root_dir.join(DEFAULT_DIR);
+        let dir_resources = match load_resources_from_folder(base_dir.clone(), None) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", fold_displayable(e.chain(), "| Caused by: ")),
+        };
+        resources.extend(
+            dir_resources
+                .into_iter()
+                .map(|resource| (base_dir.join(&resource.file_name), resource)),
+        );
+    }
+
+    // Keys as they are passed to `localize`: messages, their attributes as `message.attribute` and terms as `-term`
+    let mut known: HashSet<String> = HashSet::new();
+    for entry in resources
+        .iter()
+        .flat_map(|(_, resource)| resource.resource.entries())
+    {
+        match entry {
+            Entry::Message(message) => {
+                known.insert(message.id.name.to_string());
+                for attribute in &message.attributes {
+                    known.insert(format!("{}.{}", message.id.name, attribute.id.name));
+                }
+            }
+            Entry::Term(term) => {
+                known.insert(format!("-{}", term.id.name));
+            }
+            _ => (),
+        }
+    }
+
+    if let Some(error) = keys
+        .iter()
+        .filter(|key| !known.contains(&key.value()))
+        .map(|key| {
+            syn::Error::new(
+                key.span(),
+                format!(
+                    "Localization key \"{}\" does not exist in the default localizations",
+                    key.value()
+                ),
+            )
+        })
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+    {
+        return Err(error);
+    }
+
+    // Like the bindings, include the files so cargo checks the keys again when one of them is modified
+    let tracked_files = resources.iter().map(|(path, _)| {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+        LitStr::new(&path.to_string_lossy(), proc_macro2::Span::call_site())
+    });
+    Ok(quote! {
+        #(const _: &str = include_str!(#tracked_files);)*
+    })
+}
+
+/// The key of a `#[localization_key = "..."]` attribute
+fn key_of(attribute: &Attribute) -> Result<LitStr, syn::Error> {
+    match &attribute.meta {
+        Meta::NameValue(name_value) => match &name_value.value {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(key), ..
+            }) => Ok(key.clone()),
+            other => Err(syn::Error::new_spanned(
+                other,
+                "Expected the localization key as a string: #[localization_key = \"key\"]",
+            )),
+        },
+        other => Err(syn::Error::new_spanned(
+            other,
+            "Expected the localization key as a string: #[localization_key = \"key\"]",
+        )),
+    }
+}
@@ -1,146 +1,795 @@
 //#![feature(proc_macro_diagnostic)]
 
 use std::{
-    collections::{HashMap, HashSet},
-    sync::Arc,
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
 };
 
-use fluent_bundle::FluentResource;
+use deunicode::deunicode;
 use fluent_localization_loader::{
-    base_path, fold_displayable, load_resources_from_folder, DEFAULT_DIR,
+    base_path, fold_displayable, load_resources_from_folder, Resource, VariableKind, DEFAULT_DIR,
 };
-use fluent_syntax::ast::{Entry, Expression, InlineExpression, PatternElement};
+use fluent_syntax::{
+    ast::{
+        self, CallArguments, Comment, Entry, Expression, InlineExpression, PatternElement,
+        VariantKey,
+    },
+    parser, serializer,
+};
+use options::{MacroOptions, Style};
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::LitStr;
+use quote::{format_ident, quote};
+use syn::{Ident, LitStr};
+
+mod keys;
+mod options;
+#[cfg(test)]
+mod tests;
 
-//hardcode the alphabet, seems to be the fastest way to do this
-const ALPHABET: [char; 26] = [
-    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
-    'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+/// File in the localizations directory that is tracked for changes if present, can be touched to force new bindings to be generated
+const MANIFEST_FILE: &str = "bindgen.manifest";
+
+// Keywords (including reserved ones) that need to be escaped as raw identifiers
+const KEYWORDS: [&str; 50] = [
+    "abstract",
+    "as",
+    "async",
+    "await",
+    "become",
+    "box",
+    "break",
+    "const",
+    "continue",
+    "do",
+    "dyn",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "final",
+    "fn",
+    "for",
+    "gen",
+    "if",
+    "impl",
+    "in",
+    "let",
+    "loop",
+    "macro",
+    "match",
+    "mod",
+    "move",
+    "mut",
+    "override",
+    "priv",
+    "pub",
+    "ref",
+    "return",
+    "static",
+    "struct",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "unsafe",
+    "unsized",
+    "use",
+    "virtual",
+    "where",
+    "while",
+    "yield",
+    "union",
+    "macro_rules",
 ];
+// These can't be raw identifiers, so they get an underscore appended instead
+const UNESCAPABLE_KEYWORDS: [&str; 5] = ["self", "super", "crate", "_", "Self"];
+// Plural categories that only make sense for numbers, `other` is left out as it is also the usual default of string selects
+const PLURAL_CATEGORIES: [&str; 5] = ["zero", "one", "two", "few", "many"];
+
+// A referenced message or term (`true` for terms), with the attribute if the reference is to one of its attributes
+type Dependency<'a> = (bool, &'a str, Option<&'a str>);
+
+// A helper on the `Localizer` trait as its name, declaration, implementation for the localizer and implementation for the owned localizer
+type TraitMethod = (
+    String,
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+);
+
+/// Key of the node a dependency refers to, `message.attribute` for attributes and terms prefixed with `-` like in the files
+fn dependency_key((term, id, attribute): Dependency) -> String {
+    let prefix = if term { "-" } else { "" };
+    match attribute {
+        Some(attribute) => format!("{prefix}{id}.{attribute}"),
+        None => format!("{prefix}{id}"),
+    }
+}
 
 struct Node<'a> {
     category: &'a str,
-    name: &'a str,
+    source_file: &'a str,
+    // Line the entry starts on in its file, for pointing at it in errors
+    source_line: Option<usize>,
+    // Key of the entry, `message.attribute` for attributes
+    name: String,
+    // Whether this is an attribute of a message, rather than the message itself
+    attribute: bool,
     variables: HashSet<&'a str>,
-    dependencies: HashSet<&'a str>,
+    // Kind of the variables that are used in a way that needs a specific kind, `Unknown` for conflicting usages
+    variable_kinds: HashMap<&'a str, VariableKind>,
+    // Kinds given in the comment with `$name (Number)` or `$name (String)`, these win over the kinds from usage
+    annotated_kinds: HashMap<&'a str, VariableKind>,
+    // Variables used as selector (directly or through a function like `NUMBER($count)`) with the keys of their variants,
+    // in the order they are first used
+    selects: Vec<(&'a str, Vec<&'a str>)>,
+    // Nodes this one references. Messages hand their variables to this node, terms only get the arguments the reference passes them
+    dependencies: HashSet<Dependency<'a>>,
     term: bool,
+    no_fallback: bool,
+    // Marked with `# @no-binding`, the message is still validated and can be referenced but gets no helpers
+    no_binding: bool,
+    // Note of the `# @deprecated` directive, empty if it has none
+    deprecated: Option<&'a str>,
+    // Name given with `# @rust-name: name` to use for the helper instead of the key, starting with `::` to leave out the category
+    rust_name: Option<&'a str>,
+    // Length given with `# @max-length: 40`, as written so `check_annotations` can report the ones that aren't a number
+    max_length: Option<&'a str>,
+    // Where the text ends up, from `# @context: button label`, for the documentation of the helper
+    context: Option<&'a str>,
+    // Comment lines attached to the entry, without directives
+    comment: Vec<&'a str>,
+    // The entry as it is written in the default language, for the documentation of the helper
+    source_text: String,
+    // Whether placeables are nested deeper than `max_nesting`, the ones below that are not looked at
+    too_deep: bool,
 }
 
 impl<'a> Node<'a> {
-    pub fn new(category: &'a str, name: &'a str, term: bool) -> Self {
+    pub fn new(category: &'a str, source_file: &'a str, name: String, term: bool) -> Self {
         Node {
             category,
+            source_file,
+            source_line: None,
             name,
+            attribute: false,
             variables: HashSet::new(),
+            variable_kinds: HashMap::new(),
+            annotated_kinds: HashMap::new(),
+            selects: Vec::new(),
             dependencies: HashSet::new(),
             term,
+            no_fallback: false,
+            no_binding: false,
+            deprecated: None,
+            rust_name: None,
+            max_length: None,
+            context: None,
+            comment: Vec::new(),
+            source_text: String::new(),
+            too_deep: false,
+        }
+    }
+
+    /// Attributes for the generated helper: documentation with the comment of the entry, its default text and the variables it takes,
+    /// and `#[deprecated]` for entries marked with `# @deprecated`
+    fn helper_attributes(&self) -> proc_macro2::TokenStream {
+        let mut lines: Vec<String> = self.comment.iter().map(|line| line.to_string()).collect();
+        if let Some(context) = self.context {
+            lines.push(format!("Context: {context}"));
+        }
+        if let Some(max_length) = self.max_length() {
+            lines.push(format!("Maximum length: {max_length}"));
+        }
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+
+        lines.push("Default text:".to_string());
+        lines.push("```text".to_string());
+        lines.extend(self.source_text.lines().map(str::to_string));
+        lines.push("```".to_string());
+
+        if !self.variables.is_empty() {
+            let mut variables: Vec<&&str> = self.variables.iter().collect();
+            variables.sort_unstable_by_key(|value| value.to_lowercase());
+            lines.push(String::new());
+            lines.push(format!(
+                "Variables: {}",
+                fold_displayable(
+                    variables
+                        .into_iter()
+                        .map(|variable| format!("`${variable}`")),
+                    ", "
+                )
+            ));
+        }
+
+        // Doc attributes take string literals, so anything in the text gets escaped for us
+        let lines = lines
+            .iter()
+            .map(|line| LitStr::new(&format!(" {line}"), proc_macro2::Span::call_site()));
+        let deprecated = self.deprecated.map(|note| {
+            if note.is_empty() {
+                quote!(#[deprecated])
+            } else {
+                let note = LitStr::new(note, proc_macro2::Span::call_site());
+                quote!(#[deprecated(note = #note)])
+            }
+        });
+        quote! {
+            #(#[doc = #lines])*
+            #deprecated
+        }
+    }
+
+    /// Key of the node in the nodes map, the name with terms prefixed with `-` so a term and a message can have the same name
+    fn key(&self) -> String {
+        if self.term {
+            format!("-{}", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Length given with `# @max-length`, `None` if it has none or it isn't a number
+    fn max_length(&self) -> Option<usize> {
+        self.max_length?.parse().ok()
+    }
+
+    /// Name of the generated helper, before escaping it into a valid identifier.
+    /// Nested helpers are already grouped per category, so they don't get the category as prefix
+    fn function_name(&self, options: &MacroOptions) -> String {
+        match (self.term, options.style) {
+            _ if self.unprefixed() => self.base_name(),
+            (false, Style::Nested) => self.base_name(),
+            (false, Style::Flat) if !options.prefix => self.base_name(),
+            _ => self.flat_name(),
+        }
+    }
+
+    /// The name prefixed with the category (or `term` for terms), this is unique and used for the generated types
+    fn flat_name(&self) -> String {
+        if self.unprefixed() {
+            self.base_name()
+        } else if self.term {
+            format!("term_{}", self.base_name())
+        } else {
+            format!("{}_{}", sanitize(self.category), self.base_name())
         }
     }
+
+    /// The name without any prefix, from the `@rust-name` directive if there is one and otherwise the key.
+    /// The directive is in the comment shared with the attributes, so those get their name appended
+    fn base_name(&self) -> String {
+        let Some(rust_name) = self.rust_name else {
+            return sanitize(&self.name);
+        };
+        let rust_name = rust_name.trim_start_matches("::");
+        match self.name.split_once('.') {
+            Some((_, attribute)) if self.attribute => {
+                format!("{rust_name}_{}", sanitize(attribute))
+            }
+            _ => rust_name.to_string(),
+        }
+    }
+
+    /// Whether the `@rust-name` directive asks to leave out the category, with a leading `::`
+    fn unprefixed(&self) -> bool {
+        self.rust_name.is_some_and(|name| name.starts_with("::"))
+    }
+
+    /// Record a usage of the variable that needs a value of the given kind
+    fn expect_kind(&mut self, variable: &'a str, kind: VariableKind) {
+        self.variable_kinds
+            .entry(variable)
+            .and_modify(|existing| {
+                if *existing != kind {
+                    *existing = VariableKind::Unknown
+                }
+            })
+            .or_insert(kind);
+    }
+
+    /// Type a variable has to be convertible into. Variables that are only used as numbers take numbers,
+    /// as text passed to `NUMBER()` or a plural select doesn't fail but silently falls back to the default
+    fn value_type(&self, variable: &str) -> &'static str {
+        match self.variable_kind(variable) {
+            Some(VariableKind::Number) => "fluent_bundle::types::FluentNumber",
+            Some(VariableKind::String) if self.annotated_kinds.contains_key(variable) => {
+                "std::borrow::Cow<'a, str>"
+            }
+            _ => "fluent_bundle::FluentValue<'a>",
+        }
+    }
+
+    /// Record the keys of the variants of a select on the variable, merged with the ones of earlier selects on it
+    fn select_on(&mut self, variable: &'a str, keys: impl Iterator<Item = &'a str>) {
+        let position = match self.selects.iter().position(|(name, _)| *name == variable) {
+            Some(position) => position,
+            None => {
+                self.selects.push((variable, Vec::new()));
+                self.selects.len() - 1
+            }
+        };
+        let known = &mut self.selects[position].1;
+        for key in keys {
+            if !known.contains(&key) {
+                known.push(key);
+            }
+        }
+    }
+
+    /// Kind of a variable, as annotated in the comment or otherwise as it is used
+    fn variable_kind(&self, variable: &str) -> Option<VariableKind> {
+        self.annotated_kinds
+            .get(variable)
+            .or_else(|| self.variable_kinds.get(variable))
+            .copied()
+    }
+
+    /// Where the entry is defined, `file.ftl:line` or just the file if the line is unknown
+    fn location(&self) -> String {
+        match self.source_line {
+            Some(line) => format!("{}:{line}", self.source_file),
+            None => self.source_file.to_string(),
+        }
+    }
+
+    /// Category struct the helper is generated on with the nested style, `None` if it goes on the localizer itself
+    fn owner(&self, style: Style) -> Option<String> {
+        match style {
+            Style::Nested if !self.term && !self.unprefixed() => Some(sanitize(self.category)),
+            _ => None,
+        }
+    }
+}
+/// Check at compile time that the keys given in `#[localization_key = "key"]` attributes on the fields (or variants) of a type
+/// exist in the default localizations, for types that hold keys to localize later on.
+/// Like the `dirs` option of the bindings, `#[localization_dirs("shared/localizations", "app/localizations")]` on the type reads other directories
+#[proc_macro_derive(LocalizationKeys, attributes(localization_key, localization_dirs))]
+pub fn derive_localization_keys(input: TokenStream) -> TokenStream {
+    let input = match syn::parse::<syn::DeriveInput>(input) {
+        Ok(input) => input,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    match keys::derive(input) {
+        Ok(code) => code.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
+
 #[proc_macro]
-pub fn bind_localizations(_meta: TokenStream) -> TokenStream {
+pub fn bind_localizations(meta: TokenStream) -> TokenStream {
+    match syn::parse::<MacroOptions>(meta) {
+        Ok(options) => expand(options).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Localization directories to read, the given ones or the one of [`base_path`] if there are none
+fn root_dirs(dirs: &[LitStr]) -> Vec<PathBuf> {
+    if dirs.is_empty() {
+        vec![base_path()]
+    } else {
+        dirs.iter().map(|dir| PathBuf::from(dir.value())).collect()
+    }
+}
+
+/// Generate the bindings for the localizations the options point at, problems with them become a `compile_error!`
+fn expand(options: MacroOptions) -> proc_macro2::TokenStream {
+    // An earlier expansion on this thread that failed doesn't get to emit its warnings
+    WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+
     //Load the bundle
 
-    let mut base_dir = base_path();
-    base_dir.push(DEFAULT_DIR);
+    let root_dirs = root_dirs(&options.dirs);
 
-    let resources = match load_resources_from_folder(base_dir) {
-        Ok(value) => value,
-        Err(e) => panic!("{}", fold_displayable(e.chain(), "| Caused by: ")),
-    };
+    let mut resources: Vec<Resource> = Vec::new();
+    let mut tracked_files: Vec<LitStr> = Vec::new();
+    for root_dir in &root_dirs {
+        let base_dir = root_dir.join(DEFAULT_DIR);
+        let mut dir_resources = match load_resources_from_folder(base_dir.clone(), None) {
+            Ok(value) => value,
+            Err(e) => panic!("{}", fold_displayable(e.chain(), "| Caused by: ")),
+        };
+        dir_resources.retain(|resource| !options.excludes(&resource.file_name));
+
+        // Include every file we read so cargo knows to expand us again when one of them is modified.
+        // Newly added files can't be picked up this way, the optional manifest file can be touched to force a rebuild for those
+        let manifest = root_dir.join(MANIFEST_FILE);
+        tracked_files.extend(
+            dir_resources
+                .iter()
+                .map(|resource| base_dir.join(&resource.file_name))
+                .chain(Some(manifest).filter(|manifest| manifest.is_file()))
+                .map(|path| {
+                    let path = fs::canonicalize(&path).unwrap_or(path);
+                    LitStr::new(&path.to_string_lossy(), proc_macro2::Span::call_site())
+                }),
+        );
+        resources.extend(dir_resources);
+    }
+
+    // The runtime parser used for the resources strips comments, parse them again so we can read the directives in those
+    let parsed: Vec<(&Resource, ast::Resource<&str>)> = resources
+        .iter()
+        .map(|resource| {
+            let ast = match parser::parse(resource.resource.source()) {
+                Ok(ast) => ast,
+                Err((ast, _)) => ast,
+            };
+            (resource, ast)
+        })
+        .collect();
+
+    // Duplicates within a directory would have yielded a loading error earlier on, but later directories override the entries
+    // of earlier ones as a whole (attributes included), like `add_resource_overriding` does. Remember which resource won for every entry
+    let mut winners: HashMap<(bool, &str), usize> = HashMap::new();
+    for (index, (_, ast)) in parsed.iter().enumerate() {
+        for entry in &ast.body {
+            match entry {
+                Entry::Message(message) => winners.insert((false, message.id.name), index),
+                Entry::Term(term) => winners.insert((true, term.id.name), index),
+                _ => None,
+            };
+        }
+    }
+    let effective_messages: Vec<&ast::Message<&str>> = parsed
+        .iter()
+        .enumerate()
+        .flat_map(|(index, (_, ast))| ast.body.iter().map(move |entry| (index, entry)))
+        .filter_map(|(index, entry)| match entry {
+            Entry::Message(message) if winners[&(false, message.id.name)] == index => Some(message),
+            _ => None,
+        })
+        .collect();
 
-    // Walk each resource and generaate its nodes, then collect them all in a singular hashmap.
-    //No need to worry about duplicates since that would have yieled a loading error earlyier on
-    let mut nodes_map: HashMap<String, Node> = resources
+    // Walk each resource and generaate its nodes, then collect them all in a singular hashmap
+    let mut nodes_map: HashMap<String, Node> = parsed
         .iter()
-        .flat_map(|resource| generate_nodes_for(&resource.name, &resource.resource))
-        .map(|node| (node.name.to_string(), node))
+        .enumerate()
+        .flat_map(|(index, (resource, ast))| {
+            generate_nodes_for(resource, ast, options.max_nesting)
+                .into_iter()
+                .map(move |node| (index, node))
+        })
+        .filter(|(index, node)| {
+            let id = node.name.split('.').next().unwrap_or(&node.name);
+            winners.get(&(node.term, id)) == Some(index)
+        })
+        .map(|(_, node)| (node.key(), node))
         .collect();
 
     //Assemble full list for later, filter out terms cause we can't enforce their pressence sadly
-    let all_terms: Vec<LitStr> = nodes_map
+    // Sorted so the generated lists don't change between builds
+    let mut term_keys: Vec<&str> = nodes_map
+        .values()
+        .filter(|node| node.term && !node.attribute)
+        .map(|node| node.name.as_str())
+        .collect();
+    term_keys.sort_unstable();
+    let all_terms: Vec<LitStr> = term_keys
         .iter()
-        .filter(|(_, node)| node.term)
-        .map(|(name, _)| syn::LitStr::new(name.as_str(), proc_macro2::Span::call_site()))
+        .map(|key| syn::LitStr::new(key, proc_macro2::Span::call_site()))
         .collect();
     let term_count = all_terms.len();
-    let all_messages: Vec<LitStr> = nodes_map
+    let mut message_keys: Vec<&str> = nodes_map
+        .values()
+        .filter(|node| !node.term && !node.attribute)
+        .map(|node| node.name.as_str())
+        .collect();
+    message_keys.sort_unstable();
+    let all_messages: Vec<LitStr> = message_keys
         .iter()
-        .filter(|(_, node)| !node.term)
-        .map(|(name, _)| syn::LitStr::new(name.as_str(), proc_macro2::Span::call_site()))
+        .map(|key| syn::LitStr::new(key, proc_macro2::Span::call_site()))
         .collect();
     let message_count = all_messages.len();
+    let mut no_fallback_keys: Vec<&str> = nodes_map
+        .values()
+        .filter(|node| node.no_fallback)
+        .map(|node| node.name.as_str())
+        .collect();
+    no_fallback_keys.sort_unstable();
+    let no_fallback: Vec<LitStr> = no_fallback_keys
+        .iter()
+        .map(|key| syn::LitStr::new(key, proc_macro2::Span::call_site()))
+        .collect();
+    let no_fallback_count = no_fallback.len();
+    let mut no_binding_keys: Vec<&str> = nodes_map
+        .values()
+        .filter(|node| node.no_binding)
+        .map(|node| node.name.as_str())
+        .collect();
+    no_binding_keys.sort_unstable();
+    let no_binding: Vec<LitStr> = no_binding_keys
+        .iter()
+        .map(|key| syn::LitStr::new(key, proc_macro2::Span::call_site()))
+        .collect();
+    let no_binding_count = no_binding.len();
+    // Attributes are validated as `message.attribute`, this includes messages that only have attributes and no value
+    let mut attribute_keys: Vec<String> = effective_messages
+        .iter()
+        .flat_map(|message| {
+            message
+                .attributes
+                .iter()
+                .map(|attribute| format!("{}.{}", message.id.name, attribute.id.name))
+        })
+        .collect();
+    attribute_keys.sort_unstable();
+    let all_attributes: Vec<LitStr> = attribute_keys
+        .iter()
+        .map(|key| syn::LitStr::new(key, proc_macro2::Span::call_site()))
+        .collect();
+    let attribute_count = all_attributes.len();
+    // Messages without a value only have helpers for their attributes, but the message itself still has to exist
+    let mut attribute_only_keys: Vec<&str> = effective_messages
+        .iter()
+        .filter(|message| message.value.is_none())
+        .map(|message| message.id.name)
+        .collect();
+    attribute_only_keys.sort_unstable();
+    let attribute_only: Vec<LitStr> = attribute_only_keys
+        .iter()
+        .map(|key| syn::LitStr::new(key, proc_macro2::Span::call_site()))
+        .collect();
+    let attribute_only_count = attribute_only.len();
     //println!("{all_names:?}");
 
+    // Nodes nested too deep were only partially processed, anything reported about them after this could be wrong
+    if let Err(e) = check_nesting(&nodes_map, options.max_nesting) {
+        return e.to_compile_error();
+    }
+
+    // Report every reference to something that doesn't exist at once, rather than one per build
+    if let Err(e) = check_dangling(&nodes_map) {
+        return e.to_compile_error();
+    }
+
+    // Fluent doesn't give parse errors on cyclic references, find them before copying over dependencies so that can't loop forever
+    if let Err(e) = check_cycles(&nodes_map) {
+        return e.to_compile_error();
+    }
+
     //Nodes can depend on other nodes, copy over all the dependecies where needed
-    // ! Recursion checking required in since fluent doesn't give parse errors on these so we need to avoid infinite loops here !
-    loop {
-        // rust mutability can be a pain in the ass sometimes so we have to do this the hard way
-        let Some(todo) = nodes_map
-            .iter()
-            .filter_map(|(_, node)| node.dependencies.iter().next().map(|todo| todo.to_string()))
-            .next()
-        else {
-            break;
-        };
+    // rust mutability can be a pain in the ass sometimes so we have to do this the hard way
+    while let Some(dependency) = nodes_map
+        .values()
+        .find_map(|node| node.dependencies.iter().next().copied())
+    {
+        // Terms don't hand their variables to whatever references them, the dependency only mattered for the checks above
+        if dependency.0 {
+            for node in nodes_map.values_mut() {
+                node.dependencies.remove(&dependency);
+            }
+            continue;
+        }
 
-        let Some((variables, dependencies)) = nodes_map
+        let todo = dependency_key(dependency);
+        let (variables, variable_kinds, dependencies, source_file) = nodes_map
             .get(todo.as_str())
-            .map(|node| (node.variables.clone(), node.dependencies.clone()))
-        else {
-            panic!(
-                "Enountered a dependency on localization node {todo} but no such node was loaded"
-            );
-        };
+            .map(|node| {
+                (
+                    node.variables.clone(),
+                    node.variable_kinds.clone(),
+                    node.dependencies.clone(),
+                    node.source_file,
+                )
+            })
+            .expect("Dangling references are reported before resolving dependencies");
 
         for (name, node) in nodes_map
             .iter_mut()
-            .filter(|(_, node)| node.dependencies.contains(todo.as_str()))
+            .filter(|(_, node)| node.dependencies.contains(&dependency))
         {
-            if name.as_str() == todo.as_str() {
-                panic!("Cyclic localization loop detected at node {name}!");
+            node.dependencies.remove(&dependency);
+
+            // Pulling variables in from another file is valid, but easy to miss when editing either file
+            if node.source_file != source_file && !variables.is_empty() {
+                let mut inherited: Vec<&&str> = variables.iter().collect();
+                inherited.sort_unstable();
+                warning(format!(
+                    "Localization node {name} in {} inherits variables {} from {todo} in {source_file}",
+                    node.location(),
+                    fold_displayable(inherited.into_iter().map(|variable| format!("${variable}")), ", ")
+                ));
             }
 
-            node.dependencies.remove(todo.as_str());
             node.variables.extend(variables.iter());
-            node.dependencies.extend(dependencies.iter());
+            for (variable, kind) in &variable_kinds {
+                node.expect_kind(variable, *kind);
+            }
+            // Whatever the dependency references in turn is needed as well
+            node.dependencies.extend(dependencies.iter().copied());
         }
     }
 
+    if let Err(e) = check_annotations(&nodes_map) {
+        return e.to_compile_error();
+    }
+
+    // Different keys or variables can sanitize to the same identifier, catch those before rustc does with a far less helpful error
+    if let Err(e) = check_collisions(&nodes_map, &options) {
+        return e.to_compile_error();
+    }
+
+    // Sorted by key so the output doesn't depend on the order of the map, terms and messages can share a key
+    let mut meta_nodes: Vec<&Node> = nodes_map.values().collect();
+    meta_nodes.sort_unstable_by(|a, b| (&a.name, a.term).cmp(&(&b.name, b.term)));
+    let message_meta = meta_nodes.into_iter().map(|node| {
+        let key = &node.name;
+        let category = node.category;
+        let mut variables: Vec<&str> = node.variables.iter().copied().collect();
+        variables.sort_unstable();
+        let is_term = node.term;
+        quote! {
+            fluent_localization_loader::MessageMeta {
+                key: #key,
+                category: #category,
+                variables: &[#(#variables),*],
+                is_term: #is_term,
+            }
+        }
+    });
+
+    let language_enum = match language_enum(&root_dirs, &options) {
+        Ok(language_enum) => language_enum,
+        Err(e) => return e.to_compile_error(),
+    };
+    let test_module = match test_module(&root_dirs, &options) {
+        Ok(test_module) => test_module,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    // The directories are looked up again at runtime, relative to the working directory of the application
+    let root_dirs = if options.dirs.is_empty() {
+        quote!([fluent_localization_loader::base_path()])
+    } else {
+        let dirs = &options.dirs;
+        quote!([#(std::path::PathBuf::from(#dirs)),*])
+    };
+
+    // Visibility and name the generated items get, `pub` and `LanguageLocalizer` unless set with the options
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let owned_type = format_ident!("Owned{localizer_type}");
+
+    let excluded = &options.exclude;
+
     // General code for validating the bundle and handling errors
 
     let mut code = quote! {
-        pub const MESSAGES: [&str; #message_count] = [#(#all_messages,)*];
-        pub const TERMS: [&str; #term_count] = [#(#all_terms,)*];
+        #(const _: &str = include_str!(#tracked_files);)*
 
-        pub struct LanguageLocalizer<'a> {
+        /// Messages with a value, sorted by key
+        #vis const MESSAGES: [&str; #message_count] = [#(#all_messages,)*];
+        #vis const MESSAGE_COUNT: usize = #message_count;
+        /// Terms without their `-`, sorted by key
+        #vis const TERMS: [&str; #term_count] = [#(#all_terms,)*];
+        #vis const TERM_COUNT: usize = #term_count;
+        /// Attributes of the messages as `message.attribute`
+        #vis const ATTRIBUTES: [&str; #attribute_count] = [#(#all_attributes,)*];
+        /// Messages that only have attributes and no value, these can only be localized through their attributes
+        #vis const ATTRIBUTE_ONLY: [&str; #attribute_only_count] = [#(#attribute_only,)*];
+        /// Messages marked with `# @no-fallback`, these never fall back to the default language
+        #vis const NO_FALLBACK: [&str; #no_fallback_count] = [#(#no_fallback,)*];
+        /// Messages marked with `# @no-binding`, these are validated but don't get helpers
+        #vis const NO_BINDING: [&str; #no_binding_count] = [#(#no_binding,)*];
+        /// Every message, attribute and term with the variables it needs, sorted by key
+        #vis const MESSAGE_META: &[fluent_localization_loader::MessageMeta] = &[#(#message_meta,)*];
+
+        #vis struct #localizer_type<'a> {
             localizations: &'a fluent_localization_loader::LocalizationHolder,
             language: &'a str,
         }
 
+        /// A localizer that owns its language and shares the holder, so it can be stored or moved into a task.
+        /// It implements the `Localizer` trait, with the trait in scope `owned.base_name()` works like on the localizer with texts borrowing from it.
+        /// The localizer it hands out with `owned.localizer()` also has the argument helpers that aren't part of the trait
+        #[derive(Clone)]
+        #vis struct #owned_type {
+            localizations: std::sync::Arc<fluent_localization_loader::LocalizationHolder>,
+            language: String,
+        }
+
+        impl std::fmt::Debug for #localizer_type<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!(#localizer_type))
+                    .field("language", &self.language)
+                    .finish_non_exhaustive()
+            }
+        }
+
+        impl std::fmt::Debug for #owned_type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!(#owned_type))
+                    .field("language", &self.language)
+                    .finish_non_exhaustive()
+            }
+        }
+
+        impl #owned_type {
+            #vis fn new(holder: std::sync::Arc<fluent_localization_loader::LocalizationHolder>, language: impl Into<String>) -> #owned_type {
+                #owned_type {
+                    localizations: holder,
+                    language: language.into(),
+                }
+            }
+
+            /// Borrow a localizer for the language to call the helpers on, this is as cheap as constructing one directly
+            #vis fn localizer(&self) -> #localizer_type<'_> {
+                #localizer_type::new(&self.localizations, &self.language)
+            }
+
+            #vis fn language(&self) -> &str {
+                &self.language
+            }
+
+            #vis fn holder(&self) -> &std::sync::Arc<fluent_localization_loader::LocalizationHolder> {
+                &self.localizations
+            }
+        }
+
 
-        impl <'a> LanguageLocalizer<'a> {
-            pub fn new(holder: &'a fluent_localization_loader::LocalizationHolder, language: &'a str) -> LanguageLocalizer<'a> {
-                LanguageLocalizer {
+        impl <'a> #localizer_type<'a> {
+            #vis fn new(holder: &'a fluent_localization_loader::LocalizationHolder, language: &'a str) -> #localizer_type<'a> {
+                #localizer_type {
                     localizations: holder,
                     language,
                 }
             }
 
 
-            pub fn validate_default_bundle_complete() -> anyhow::Result<()> {
+            /// Check how much of the keys of the bindings every language directory translates, failing if any language covers
+            /// less than `min_coverage` (from 0.0 to 1.0). The summary lists the missing keys of every language
+            #vis fn validate_all_bundles(min_coverage: f32) -> anyhow::Result<fluent_localization_loader::CoverageSummary> {
+                tracing::debug!("Validating the coverage of all language bundles");
+                let summary = Self::bundle_coverage(&#root_dirs)?;
+
+                let below: Vec<String> = summary.below(min_coverage).map(|(language, coverage)| {
+                    format!("{language} ({:.1}%, missing {})", coverage.coverage * 100.0, fluent_localization_loader::fold_displayable(coverage.missing.iter(), ", "))
+                }).collect();
+                if !below.is_empty() {
+                    Err(fluent_localization_loader::LocalizationLoadingError::new(format!("The following languages cover less than {:.1}% of the localization keys: {}", min_coverage * 100.0, fluent_localization_loader::fold_displayable(below.iter(), "; "))))?
+                }
+
+                Ok(summary)
+            }
+
+            /// Coverage of the languages in the given localization directories
+            fn bundle_coverage(root_dirs: &[std::path::PathBuf]) -> anyhow::Result<fluent_localization_loader::CoverageSummary> {
+                let expected: Vec<&str> = MESSAGES.into_iter().chain(ATTRIBUTES).collect();
+                Ok(fluent_localization_loader::CoverageSummary::collect(root_dirs, &expected, |file_name| {
+                    file_name.starts_with('_') || [#(#excluded),*].contains(&file_name)
+                })?)
+            }
+
+            #vis fn validate_default_bundle_complete() -> anyhow::Result<()> {
+                Self::validate_default_bundle_in(&#root_dirs)
+            }
+
+            /// `validate_default_bundle_complete` for the given localization directories
+            fn validate_default_bundle_in(root_dirs: &[std::path::PathBuf]) -> anyhow::Result<()> {
                 tracing::debug!("Validating default bundle has all expected keys");
-                let mut base_dir = fluent_localization_loader::base_path();
                 let default_lang = fluent_localization_loader::get_default_language()?;
 
-                base_dir.push(default_lang.to_string());
-
-                let resources = fluent_localization_loader::load_resources_from_folder(base_dir)?;
+                let mut resources = Vec::new();
+                for root_dir in root_dirs {
+                    let base_dir = root_dir.join(default_lang.to_string());
+                    resources.extend(fluent_localization_loader::load_resources_from_folder(base_dir, None)?);
+                }
+                // Files excluded from the bindings don't have to be complete either
+                resources.retain(|resource| !resource.file_name.starts_with('_') && ![#(#excluded),*].contains(&resource.file_name.as_str()));
 
                 let mut found_messages: std::collections::HashSet<String> = std::collections::HashSet::new();
                 let mut found_terms: std::collections::HashSet<String> = std::collections::HashSet::new();
+                let mut found_attributes: std::collections::HashSet<String> = std::collections::HashSet::new();
+                let mut found_ids: std::collections::HashSet<String> = std::collections::HashSet::new();
 
                 resources.iter()
                 .flat_map(|resource| resource.resource.entries())
@@ -150,6 +799,10 @@ pub fn bind_localizations(_meta: TokenStream) -> TokenStream {
                             if message.value.is_some()  {
                                 found_messages.insert(message.id.name.to_string());
                             }
+                            found_ids.insert(message.id.name.to_string());
+                            for attribute in &message.attributes {
+                                found_attributes.insert(format!("{}.{}", message.id.name, attribute.id.name));
+                            }
                         }
                         fluent_syntax::ast::Entry::Term(term) => {
                             found_terms.insert(term.id.name.to_string());
@@ -160,33 +813,169 @@ pub fn bind_localizations(_meta: TokenStream) -> TokenStream {
 
                 let missing_messages: Vec<&str> = MESSAGES.into_iter().filter(|name| !found_messages.contains(&name.to_string())).collect();
                 let missing_terms: Vec<&str> = TERMS.into_iter().filter(|name| !found_terms.contains(&name.to_string())).collect();
-                if missing_messages.is_empty() && missing_terms.is_empty()  {
-                    tracing::info!("Default bundle ({default_lang}) is valid");
-                    Ok(())
+                let missing_attributes: Vec<&str> = ATTRIBUTES.into_iter().filter(|name| !found_attributes.contains(&name.to_string())).collect();
+                let missing_attribute_only: Vec<&str> = ATTRIBUTE_ONLY.into_iter().filter(|name| !found_ids.contains(&name.to_string())).collect();
+                if !missing_messages.is_empty() || !missing_terms.is_empty() || !missing_attributes.is_empty() || !missing_attribute_only.is_empty() {
+                    Err(fluent_localization_loader::LocalizationLoadingError::new(format!("The following localization keys where not found in the default language bundle: {}", fluent_localization_loader::fold_displayable(missing_messages.into_iter().map(|name| name.to_string()).chain(missing_terms.into_iter().map(|name| format!("-{name}"))).chain(missing_attributes.into_iter().map(|name| name.to_string())).chain(missing_attribute_only.into_iter().map(|name| format!("{name} (attributes only)"))), ", "))))?
+                }
+
+                // The helpers pass the variables the messages had at compile time, anything else degrades the formatted message
+                let runtime_variables = fluent_localization_loader::message_variables(&resources);
+                let list = |variables: Vec<&str>| if variables.is_empty() {
+                    "no variables".to_string()
                 } else {
-                    Err(fluent_localization_loader::LocalizationLoadingError::new(format!("The following localization keys where not found in the default language bundle: {}", fluent_localization_loader::fold_displayable(missing_messages.into_iter().map(|name| name.to_string()).chain(missing_terms.into_iter().map(|name| format!("-{name}"))), ", "))))?
+                    fluent_localization_loader::fold_displayable(variables.into_iter().map(|variable| format!("${variable}")), ", ")
+                };
+                let changed: Vec<String> = MESSAGE_META.iter()
+                    .filter(|meta| !meta.is_term)
+                    .filter_map(|meta| {
+                        let found = runtime_variables.get(meta.key)?;
+                        if found.iter().map(String::as_str).eq(meta.variables.iter().copied()) {
+                            return None;
+                        }
+                        Some(format!("{} (the bindings pass {}, the file uses {})", meta.key, list(meta.variables.to_vec()), list(found.iter().map(String::as_str).collect())))
+                    })
+                    .collect();
+                if !changed.is_empty() {
+                    Err(fluent_localization_loader::LocalizationLoadingError::new(format!("The following messages in the default language bundle use other variables than the bindings were generated for: {}", fluent_localization_loader::fold_displayable(changed.iter(), "; "))))?
                 }
+
+                tracing::info!("Default bundle ({default_lang}) is valid");
+                Ok(())
+            }
+
+            /// Localize a message, the text is owned so it stays valid when the holder swaps in other languages.
+            /// If the language lost the message (or its value) it falls back to the text of the default language, so a translation lagging behind never panics
+            #vis fn localize(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> std::borrow::Cow<'a, str> {
+                self.localize_borrowed_args(name, arguments.as_ref())
             }
 
-            pub fn localize(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> String {
-                let bundle = self.localizations.get_bundle(self.language);
-                //This is autogenerated from the same list as the bundle validator so we know this is present
-                let message = bundle.get_message(name).unwrap();
+            /// Like `localize`, but also telling which language the text is in and whether that is because the language of the localizer
+            /// didn't have the message, to find missing translations from what is actually localized
+            #vis fn localize_detailed(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> fluent_localization_loader::LocalizeOutcome<'a> {
+                self.localize_detailed_borrowed_args(name, arguments.as_ref())
+            }
 
-                let mut errors = Vec::new();
+            // The arguments are only borrowed for formatting, this lets the argument builders format more than once
+            fn localize_borrowed_args(&self, name: &str, arguments: Option<&fluent_bundle::FluentArgs<'a>>) -> std::borrow::Cow<'a, str> {
+                self.localize_detailed_borrowed_args(name, arguments).text
+            }
 
-                let message = bundle.format_pattern(message.value().unwrap(), arguments.as_ref(), &mut errors);
+            fn localize_detailed_borrowed_args(&self, name: &str, arguments: Option<&fluent_bundle::FluentArgs<'a>>) -> fluent_localization_loader::LocalizeOutcome<'a> {
+                let no_fallback = NO_FALLBACK.contains(&name);
+                // The default language doesn't have a value for these either, so don't bother falling back
+                let attribute_only = ATTRIBUTE_ONLY.contains(&name);
+                let error = if attribute_only {
+                    fluent_localization_loader::LocalizeError::MissingValue(name.to_string())
+                } else if no_fallback && !self.localizations.translates_message(self.language, name) {
+                    fluent_localization_loader::LocalizeError::MissingMessage(name.to_string())
+                } else {
+                    match self.localizations.try_format_message(self.language, name, arguments) {
+                        Ok(message) => return fluent_localization_loader::LocalizeOutcome::new(message, self.localizations.message_language(self.language, name), self.language),
+                        Err(error) => error,
+                    }
+                };
+
+                let error = match error {
+                    fluent_localization_loader::LocalizeError::FormatErrors { .. } => error,
+                    _ if no_fallback || attribute_only => error,
+                    _ => {
+                        tracing::warn!("{error} in {}, falling back to the default language", self.language);
+                        let default_language = self.localizations.default_language();
+                        match self.localizations.try_format_message(&default_language, name, arguments) {
+                            Ok(message) => return fluent_localization_loader::LocalizeOutcome::new(message, default_language, self.language),
+                            Err(error) => error,
+                        }
+                    }
+                };
+
+                let message = std::borrow::Cow::Owned(self.handle_errors(name, error.into_fluent_errors()));
+                fluent_localization_loader::LocalizeOutcome::new(message, self.language, self.language)
+            }
+
+            /// Localize a message with or without the isolation marks fluent puts around placeables (`\u{2068}` and `\u{2069}`).
+            /// The bundles are shared so they always isolate, without isolation the marks are stripped from the result afterwards
+            #vis fn localize_isolating(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>, isolating: bool) -> std::borrow::Cow<'a, str> {
+                let message = self.localize(name, arguments);
+                if isolating {
+                    message
+                } else {
+                    fluent_localization_loader::strip_isolation_marks(message)
+                }
+            }
+
+            /// Localize an attribute of a message, the same as localizing `name.attribute`
+            #vis fn localize_attr(&self, name: &str, attribute: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> std::borrow::Cow<'a, str> {
+                self.localize(&format!("{name}.{attribute}"), arguments)
+            }
 
+            /// Localize a message into an existing buffer, writing it as it is formatted without allocating the text first.
+            /// It falls back like `localize` does and writes the same failure message for messages that can't be found. What is written can't be
+            /// taken back, so a message that fails to format is written with fluent's placeholders for what failed (like `{$user}`) and the errors are logged
+            #vis fn localize_to(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>, out: &mut impl std::fmt::Write) -> std::fmt::Result {
+                let no_fallback = NO_FALLBACK.contains(&name);
+                let attribute_only = ATTRIBUTE_ONLY.contains(&name);
+                let mut errors = Vec::new();
+                let error = if attribute_only {
+                    fluent_localization_loader::LocalizeError::MissingValue(name.to_string())
+                } else if no_fallback && !self.localizations.translates_message(self.language, name) {
+                    fluent_localization_loader::LocalizeError::MissingMessage(name.to_string())
+                } else {
+                    match self.localizations.write_message(self.language, name, arguments.as_ref(), out, &mut errors) {
+                        Ok(result) => return self.log_write_errors(name, errors, result),
+                        Err(error) => error,
+                    }
+                };
 
-                if errors.is_empty() {
-                    message.to_string()
+                let error = if no_fallback || attribute_only {
+                    error
                 } else {
-                    self.handle_errors(name, errors)
+                    tracing::warn!("{error} in {}, falling back to the default language", self.language);
+                    match self.localizations.write_message(&self.localizations.default_language(), name, arguments.as_ref(), out, &mut errors) {
+                        Ok(result) => return self.log_write_errors(name, errors, result),
+                        Err(error) => error,
+                    }
+                };
+
+                out.write_str(&self.handle_errors(name, error.into_fluent_errors()))
+            }
+
+            // The text of a message that failed to format halfway was already written, so the errors can only be logged
+            fn log_write_errors(&self, name: &str, errors: Vec<fluent_bundle::FluentError>, result: std::fmt::Result) -> std::fmt::Result {
+                if !errors.is_empty() {
+                    self.handle_errors(name, errors);
+                }
+                result
+            }
+
+            /// Localize a message without falling back to the default text on failure, messages that can't be formatted completely are returned as an error
+            #vis fn try_localize(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+                self.try_localize_borrowed_args(name, arguments.as_ref())
+            }
+
+            #vis fn try_localize_attr(&self, name: &str, attribute: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+                self.try_localize(&format!("{name}.{attribute}"), arguments)
+            }
+
+            fn try_localize_borrowed_args(&self, name: &str, arguments: Option<&fluent_bundle::FluentArgs<'a>>) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+                if NO_FALLBACK.contains(&name) && !self.localizations.translates_message(self.language, name) {
+                    return Err(fluent_localization_loader::LocalizeError::MissingMessage(name.to_string()));
+                }
+
+                self.localizations.try_format_message(self.language, name, arguments)
+            }
+
+            /// The value of a message that is a single element (like `{ $count }` or `{ NUMBER($amount) }`) without formatting it into a string.
+            /// Returns `None` for any other message, see `LocalizationHolder::message_value`
+            #vis fn localize_value(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> Option<fluent_bundle::FluentValue<'static>> {
+                if NO_FALLBACK.contains(&name) && !self.localizations.translates_message(self.language, name) {
+                    return None;
                 }
 
+                self.localizations.message_value(self.language, name, arguments.as_ref())
             }
 
-            pub fn handle_errors(&self, name: &str, errors: Vec<fluent_bundle::FluentError>) -> String {
+            #vis fn handle_errors(&self, name: &str, errors: Vec<fluent_bundle::FluentError>) -> String {
                 let errors = fluent_localization_loader::fold_displayable(errors.into_iter(), ", ");
                 tracing::error!("Failed to localize {name} due to following errors: {errors}");
 
@@ -200,174 +989,1737 @@ pub fn bind_localizations(_meta: TokenStream) -> TokenStream {
         }
     };
 
-    //Now let's generate the helper functions, just from strings now cause that's easier with all the damn generics
+    match message_key_enum(&nodes_map, &options) {
+        Ok(message_keys) => code.extend(message_keys),
+        Err(e) => return e.to_compile_error(),
+    }
+    code.extend(variable_kinds(&nodes_map, &options));
+    code.extend(select_variants(&nodes_map, &options));
+    code.extend(max_lengths(&nodes_map, &options));
+    code.extend(enabled_languages(&options));
+    code.extend(language_enum);
+    code.extend(test_module);
+
+    //Now let's generate the helper functions
+
+    // Nested helpers live on a struct that borrows the localizer
+    let localizer = match options.style {
+        Style::Flat => quote!(self),
+        Style::Nested => quote!(self.localizer),
+    };
+    // Prefix, return type and localize function of the helpers to generate for every message, the `try_` ones are optional.
+    // The last part is the return type on the `Localizer` trait, which borrows from the localizer as the trait has no lifetime
+    let mut flavours = vec![(
+        "",
+        quote!(std::borrow::Cow<'a, str>),
+        format_ident!("localize"),
+        quote!(std::borrow::Cow<'s, str>),
+    )];
+    if options.fallible {
+        flavours.push((
+            "try_",
+            quote!(Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError>),
+            format_ident!("try_localize"),
+            quote!(Result<std::borrow::Cow<'s, str>, fluent_localization_loader::LocalizeError>),
+        ));
+    }
+
+    // Every helper is also declared on the `Localizer` trait, nested ones under their flat name
+    let mut trait_methods: Vec<TraitMethod> = Vec::new();
+
+    // Sorted so the helpers are generated in the same order every time, rather than the random order of the map
+    let mut helper_nodes: Vec<&Node> = nodes_map
+        .values()
+        .filter(|node| !node.term && !node.no_binding)
+        .collect();
+    helper_nodes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
 
     // let's start easy: no params here
-    let start = String::from("impl <'a> LanguageLocalizer<'a> {");
-    let mut simple_block = nodes_map
+    let simple_functions = helper_nodes
         .iter()
-        .filter(|(_, node)| node.variables.is_empty() && !node.term)
-        .map(|(name, node)| {
-            let category = sanitize(node.category);
-            let sanitized_name = sanitize(name);
-            format!(
-                "
-\tpub fn {category}_{sanitized_name}(&self) -> String {{
-\t\tself.localize(\"{name}\", None)
-\t}}"
-            )
+        .filter(|node| node.variables.is_empty())
+        .map(|node| {
+            let docs = node.helper_attributes();
+            let name = LitStr::new(&node.name, proc_macro2::Span::call_site());
+            let mut helper = proc_macro2::TokenStream::new();
+            for (prefix, output, localize, trait_output) in &flavours {
+                let function_name = format_ident!(
+                    "{}",
+                    identifier(
+                        format!("{prefix}{}", node.function_name(&options)),
+                        &node.name
+                    )?
+                );
+                helper.extend(quote! {
+                    #docs
+                    #vis fn #function_name(&self) -> #output {
+                        #localizer.#localize(#name, None)
+                    }
+                });
+                trait_methods.push(trait_method(
+                    node,
+                    prefix,
+                    &function_name,
+                    quote!(),
+                    quote!(),
+                    trait_output,
+                    &docs,
+                    &options,
+                )?);
+            }
+            Ok((node.owner(options.style), helper))
         })
-        .fold(start, |assembled, extra| assembled + "\n" + &extra);
-    simple_block += "\n}";
-    //println!("{simple_block}");
-    let compiled_simple_block = simple_block
-        .parse::<proc_macro2::TokenStream>()
-        .expect("Failed to assemble simple block token stream");
-
-    code.extend(compiled_simple_block);
+        .collect::<Result<Vec<(Option<String>, proc_macro2::TokenStream)>, syn::Error>>();
+    let mut helpers = match simple_functions {
+        Ok(helpers) => helpers,
+        Err(e) => return e.to_compile_error(),
+    };
 
     //Now it gets real, welcome to generated generics
     // ! sorting is needed on the names because otherwise their order is random and not consistent between compilations!
-    let hell = nodes_map
+    let hell_functions = helper_nodes
         .iter()
-        .filter(|(_, node)| !node.variables.is_empty() && !node.term)
-        .map(|(name, node)| {
-            let count = node.variables.len();
-            let letters = get_letters(count);
+        .filter(|node| !node.variables.is_empty())
+        .map(|node| {
+            let mut variables: Vec<&&str> = node.variables.iter().collect();
+            variables.sort_unstable_by_key(|value| value.to_lowercase());
+
+            let parameters = variables
+                .iter()
+                .map(|variable| identifier(sanitize(variable), variable))
+                .collect::<Result<Vec<String>, syn::Error>>()?;
+            let taken: HashSet<String> = parameters.iter().cloned().collect();
+            let generics: Vec<Ident> = get_generic_names(variables.len(), &taken)
+                .iter()
+                .map(|generic| format_ident!("{generic}"))
+                .collect();
+            let parameters: Vec<Ident> = parameters
+                .iter()
+                .map(|parameter| format_ident!("{parameter}"))
+                .collect();
+            let types = variables
+                .iter()
+                .map(|variable| syn::parse_str::<syn::Type>(node.value_type(variable)))
+                .collect::<Result<Vec<syn::Type>, syn::Error>>()?;
+            // Generic methods would keep the trait from being used as `dyn Localizer`, so it takes the values themselves
+            let trait_types = variables
+                .iter()
+                .map(|variable| syn::parse_str::<syn::Type>(&node.value_type(variable).replace("'a", "'s")))
+                .collect::<Result<Vec<syn::Type>, syn::Error>>()?;
 
-            let generics = format!("<{}>", fold_displayable(letters.iter(), ", "));
+            for variable in &variables {
+                if node.variable_kind(variable) == Some(VariableKind::Unknown) {
+                    warning(format!(
+                        "Variable ${variable} of localization node {} in {} is used both as a number and as text, its helper accepts any value",
+                        node.name,
+                        node.location()
+                    ));
+                }
+            }
 
-            let generic_definitions = letters
+            // `FluentArgs` is a vec kept sorted by key and the keys are borrowed statics, so building it doesn't hash or allocate keys.
+            // Listing the arguments in byte order of their keys (rather than the case insensitive order of the parameters) makes
+            // every insert an append, leaving nothing worth caching in a template per message
+            let mut arguments: Vec<(&str, proc_macro2::TokenStream)> = variables
                 .iter()
-                .map(|letter| format!("\t{letter}: Into<fluent_bundle::FluentValue<'a>>,"))
-                .fold(String::from("where"), |assembled, extra| {
-                    assembled + "\n" + &extra
+                .zip(parameters.iter().zip(types.iter()))
+                .map(|(key, (parameter, value_type))| {
+                    let literal = LitStr::new(key, proc_macro2::Span::call_site());
+                    (
+                        **key,
+                        quote!((#literal, fluent_bundle::FluentValue::from(Into::<#value_type>::into(#parameter))),),
+                    )
                 })
-                + "\n";
-
-            let mut variables: Vec<&&str> = node.variables.iter().collect();
-            variables.sort_unstable_by_key(|value| value.to_lowercase());
+                .collect();
+            arguments.sort_unstable_by_key(|(key, _)| *key);
+            // Collected without a local variable, so it can't be shadowed by (or shadow) a parameter
+            let arguments = arguments.into_iter().map(|(_, argument)| argument);
+            let handle_arguments = quote!([#(#arguments)*]);
 
-            let mut letter_iter = letters.iter();
-            let mut params = String::from("&self");
-            let mut handle_arguments =
-                String::from("let mut arguments = fluent_bundle::FluentArgs::new();");
-            for name in variables {
-                let sanitized_name = sanitize(name);
-                // safe to unwrap, we generated the letters based on the variable count above
-                let letter = letter_iter.next().unwrap();
-
-                params += &format!(", {sanitized_name}: {letter}");
-                handle_arguments +=
-                    &format!("\n\t\targuments.set(\"{name}\", {sanitized_name}.into());");
-            }
-
-            let category = node.category;
-            let sanitized_name = sanitize(name);
-            format!(
-                "
-\tpub fn {category}_{sanitized_name}{generics}({params}) -> String
-\t{generic_definitions}\t{{
-\t\t{handle_arguments}
-\t\tself.localize(\"{name}\", Some(arguments))
-\t}}"
-            )
+            let docs = node.helper_attributes();
+            let name = LitStr::new(&node.name, proc_macro2::Span::call_site());
+            let mut helper = proc_macro2::TokenStream::new();
+            for (prefix, output, localize, trait_output) in &flavours {
+                let function_name = format_ident!(
+                    "{}",
+                    identifier(format!("{prefix}{}", node.function_name(&options)), &node.name)?
+                );
+                helper.extend(quote! {
+                    #docs
+                    #[allow(clippy::too_many_arguments)]
+                    #vis fn #function_name<#(#generics),*>(&self, #(#parameters: #generics),*) -> #output
+                    where
+                        #(#generics: Into<#types>,)*
+                    {
+                        #localizer.#localize(#name, Some(<fluent_bundle::FluentArgs as std::iter::FromIterator<_>>::from_iter(#handle_arguments)))
+                    }
+                });
+                trait_methods.push(trait_method(
+                    node,
+                    prefix,
+                    &function_name,
+                    quote!(#(#parameters: #trait_types),*),
+                    quote!(#(#parameters),*),
+                    trait_output,
+                    &docs,
+                    &options,
+                )?);
+            }
+            Ok((node.owner(options.style), helper))
         })
-        .fold(
-            String::from("impl <'a> LanguageLocalizer<'a> {"),
-            |assembled, extra| assembled + "\n" + &extra,
-        )
-        + "\n}";
-
-    //println!("{hell}");
-    let compiled_hell_block = hell
-        .parse::<proc_macro2::TokenStream>()
-        .expect("Failed to assemble the token stream from hell");
+        .collect::<Result<Vec<(Option<String>, proc_macro2::TokenStream)>, syn::Error>>();
+    match hell_functions {
+        Ok(functions) => helpers.extend(functions),
+        Err(e) => return e.to_compile_error(),
+    }
 
-    code.extend(compiled_hell_block);
+    // Named alternative to the positional helpers, for messages with enough variables to make those hard to read
+    if options.args {
+        let args_helpers = helper_nodes
+            .iter()
+            .filter(|node| !node.variables.is_empty())
+            .map(|node| args_helper(node, &options))
+            .collect::<Result<Vec<_>, syn::Error>>();
+        match args_helpers {
+            Ok(args_helpers) => {
+                for (owner, helper, args_struct) in args_helpers {
+                    helpers.push((owner, helper));
+                    code.extend(args_struct);
+                }
+            }
+            Err(e) => return e.to_compile_error(),
+        }
+    }
 
-    code.into()
-}
+    // Builder alternative for messages with variables that are optional or easier to set one at a time
+    if options.builder_args {
+        let builder_helpers = helper_nodes
+            .iter()
+            .filter(|node| !node.variables.is_empty())
+            .map(|node| builder_helper(node, &options))
+            .collect::<Result<Vec<_>, syn::Error>>();
+        match builder_helpers {
+            Ok(builder_helpers) => {
+                for (owner, helper, builder) in builder_helpers {
+                    helpers.push((owner, helper));
+                    code.extend(builder);
+                }
+            }
+            Err(e) => return e.to_compile_error(),
+        }
+    }
 
-fn sanitize(original: &str) -> String {
-    original.replace('-', "_").to_lowercase()
-}
+    match assemble_helpers(helpers, &options) {
+        Ok(assembled) => code.extend(assembled),
+        Err(e) => return e.to_compile_error(),
+    }
 
-fn get_letters(amount: usize) -> Vec<char> {
-    if amount > 26 {
-        todo!("Localization strings with 26+ params, what the hell is this? are we assembling a phone book?");
+    trait_methods.sort_unstable_by(|(a, _, _, _), (b, _, _, _)| a.cmp(b));
+    let mut declarations = Vec::new();
+    let mut implementations = Vec::new();
+    let mut owned_implementations = Vec::new();
+    for (_, declaration, implementation, owned_implementation) in trait_methods {
+        declarations.push(declaration);
+        implementations.push(implementation);
+        owned_implementations.push(owned_implementation);
     }
-    (0..amount).map(|count| ALPHABET[count]).collect()
-}
+    code.extend(quote! {
+        /// The helpers of the localizer as a trait, so code can take any localizer (or store one as `Box<dyn Localizer>`) and tests can
+        /// substitute their own, returning fixed strings for example. Helpers of the nested style are part of it under their flat name
+        /// (`base_name` for `localizer.base().name()`). Texts borrow from the localizer, as implementations can't borrow for longer.
+        /// It is implemented by the owned localizer, and by localizers borrowing a holder that lives as long as the program
+        #vis trait Localizer: Send + Sync + 'static {
+            #(#declarations)*
+        }
 
-fn generate_nodes_for<'a>(parrent: &'a str, resource: &'a Arc<FluentResource>) -> Vec<Node<'a>> {
-    let mut out = Vec::new();
+        #[allow(deprecated)]
+        impl Localizer for #localizer_type<'static> {
+            #(#implementations)*
+        }
 
-    for entry in resource.entries() {
-        let (name, pattern, term) = match entry {
-            Entry::Message(message) => {
-                let Some(pattern) = &message.value else {
-                    continue;
+        #[allow(deprecated)]
+        impl Localizer for #owned_type {
+            #(#owned_implementations)*
+        }
+    });
+
+    // Terms are normally only referenced from other messages, but tooling might want to preview them
+    if options.terms {
+        code.extend(quote! {
+            impl <'a> #localizer_type<'a> {
+                #vis fn localize_term(&self, name: &str, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> std::borrow::Cow<'a, str> {
+                    let mut errors = Vec::new();
+
+                    let Some(term) = self.localizations.format_term(self.language, name, arguments.as_ref(), &mut errors) else {
+                        errors.push(fluent_bundle::FluentError::ResolverError(fluent_bundle::resolver::ResolverError::Reference(
+                            fluent_bundle::resolver::errors::ReferenceKind::Term { id: name.to_string(), attribute: None },
+                        )));
+                        return std::borrow::Cow::Owned(self.handle_errors(name, errors));
+                    };
+
+                    if errors.is_empty() {
+                        term
+                    } else {
+                        std::borrow::Cow::Owned(self.handle_errors(name, errors))
+                    }
+                }
+            }
+        });
+
+        // Sorted like the other helpers, so they are generated in the same order every time
+        let mut term_nodes: Vec<&Node> = nodes_map
+            .values()
+            .filter(|node| node.term && !node.attribute)
+            .collect();
+        term_nodes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        let term_functions = term_nodes
+            .into_iter()
+            .map(|node| {
+                let function_name = format_ident!(
+                    "{}",
+                    identifier(node.function_name(&options), &node.name)?
+                );
+                let name = LitStr::new(&node.name, proc_macro2::Span::call_site());
+                let docs = node.helper_attributes();
+                Ok(quote! {
+                    #docs
+                    #vis fn #function_name(&self, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> std::borrow::Cow<'a, str> {
+                        self.localize_term(#name, arguments)
+                    }
+                })
+            })
+            .collect::<Result<Vec<proc_macro2::TokenStream>, syn::Error>>();
+        match term_functions {
+            Ok(functions) => code.extend(quote! {
+                impl <'a> #localizer_type<'a> {
+                    #(#functions)*
+                }
+            }),
+            Err(e) => return e.to_compile_error(),
+        }
+    }
+
+    // Everything only refers to itself by name and to other crates by their full path, so it can be moved into a module as is
+    if let Some(module) = options.module() {
+        code = quote! {
+            #vis mod #module {
+                #code
+            }
+
+            #vis use #module::#localizer_type;
+        };
+    }
+
+    if let Some(path) = options.dump_path() {
+        dump(&path, &code);
+    }
+
+    code.extend(emit_warnings());
+    code
+}
+
+/// Write the generated code to a file for inspection, pretty printed with the `pretty` feature.
+/// This is only an aid, failing to write it doesn't fail the build
+fn dump(path: &Path, code: &proc_macro2::TokenStream) {
+    #[cfg(feature = "pretty")]
+    let text = match syn::parse2::<syn::File>(code.clone()) {
+        Ok(file) => prettyplease::unparse(&file),
+        Err(_) => code.to_string(),
+    };
+    #[cfg(not(feature = "pretty"))]
+    let text = code.to_string();
+
+    if let Err(e) = fs::write(path, text) {
+        warning(format!(
+            "Failed to write the generated localization bindings to {}: {e}",
+            path.display()
+        ));
+    }
+}
+
+thread_local! {
+    // Warnings of the expansion that is running on this thread, emitted at the end of it
+    static WARNINGS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Emit a warning for the macro user. `proc_macro::Diagnostic` is still nightly only and cargo hides what the macro prints,
+/// so warnings are collected until [`emit_warnings`] turns them into code rustc warns about
+fn warning(message: String) {
+    WARNINGS.with(|warnings| warnings.borrow_mut().push(message));
+}
+
+/// A use of a deprecated constant for every collected warning, rustc reports those with the note as the message.
+/// They can be silenced like any deprecation, with `#![allow(deprecated)]` in the module that invokes the macro
+fn emit_warnings() -> proc_macro2::TokenStream {
+    let warnings = WARNINGS.with(|warnings| warnings.take());
+    let warnings = warnings.iter().map(|warning| {
+        let note = LitStr::new(warning, proc_macro2::Span::call_site());
+        quote! {
+            const _: () = {
+                #[deprecated(note = #note)]
+                #[allow(non_upper_case_globals)]
+                const fluent_localization_warning: () = ();
+                fluent_localization_warning
+            };
+        }
+    });
+
+    quote!(#(#warnings)*)
+}
+
+/// Enum with a variant per message, for picking the message to localize at runtime without losing track of which keys exist
+fn message_key_enum(
+    nodes_map: &HashMap<String, Node>,
+    options: &MacroOptions,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let mut nodes: Vec<&Node> = nodes_map
+        .values()
+        .filter(|node| !node.term && !node.attribute)
+        .collect();
+    nodes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let mut variants = Vec::new();
+    let mut keys = Vec::new();
+    let mut taken: HashMap<String, &Node> = HashMap::new();
+    for node in nodes {
+        let variant = identifier(camel_case(&node.flat_name()), &node.name)?;
+        if let Some(other) = taken.insert(variant.clone(), node) {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "Localization keys \"{}\" ({}) and \"{}\" ({}) both generate the MessageKey variant {variant}",
+                    other.name,
+                    other.location(),
+                    node.name,
+                    node.location()
+                ),
+            ));
+        }
+        variants.push(format_ident!("{variant}"));
+        keys.push(LitStr::new(&node.name, proc_macro2::Span::call_site()));
+    }
+    let count = variants.len();
+
+    Ok(quote! {
+        /// Every message that has bindings, for choosing the message to localize at runtime
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum MessageKey {
+            #(#variants,)*
+        }
+
+        impl MessageKey {
+            #vis const ALL: [MessageKey; #count] = [#(MessageKey::#variants,)*];
+
+            /// The key of the message as it is written in the fluent files
+            #vis fn as_str(&self) -> &'static str {
+                match *self {
+                    #(MessageKey::#variants => #keys,)*
+                }
+            }
+        }
+
+        impl std::fmt::Display for MessageKey {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for MessageKey {
+            type Err = fluent_localization_loader::LocalizationLoadingError;
+
+            fn from_str(key: &str) -> Result<Self, Self::Err> {
+                match key {
+                    #(#keys => Ok(MessageKey::#variants),)*
+                    _ => Err(fluent_localization_loader::LocalizationLoadingError::new(format!("Unknown localization key {key}"))),
+                }
+            }
+        }
+
+        impl <'a> #localizer_type<'a> {
+            #vis fn localize_key(&self, key: MessageKey, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> std::borrow::Cow<'a, str> {
+                self.localize(key.as_str(), arguments)
+            }
+
+            #vis fn try_localize_key(&self, key: MessageKey, arguments: Option<fluent_bundle::FluentArgs<'a>>) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+                self.try_localize(key.as_str(), arguments)
+            }
+        }
+    })
+}
+
+/// Names of the language directories next to the default one, in any of the localization directories
+fn language_dirs(root_dirs: &[PathBuf]) -> Result<BTreeSet<String>, syn::Error> {
+    let mut languages = BTreeSet::new();
+    for root_dir in root_dirs {
+        let entries = fs::read_dir(root_dir).map_err(|e| {
+            syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!(
+                    "Failed to list the languages in {}: {e}",
+                    root_dir.display()
+                ),
+            )
+        })?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Metadata rather than the file type of the entry, so symlinked language directories are followed
+            if name != DEFAULT_DIR
+                && !name.starts_with('.')
+                && fs::metadata(entry.path()).is_ok_and(|meta| meta.is_dir())
+            {
+                languages.insert(name);
+            }
+        }
+    }
+
+    Ok(languages)
+}
+
+/// Tests loading and validating the localizations with the `generate_tests` option, a test per language checks it is complete.
+/// The directories are the ones the macro used, rather than resolving them again relative to wherever the tests run
+fn test_module(
+    root_dirs: &[PathBuf],
+    options: &MacroOptions,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    if !options.generate_tests {
+        return Ok(quote!());
+    }
+
+    let localizer_type = &options.struct_name;
+    let dirs: Vec<String> = root_dirs
+        .iter()
+        .map(|dir| {
+            fs::canonicalize(dir)
+                .unwrap_or_else(|_| dir.clone())
+                .to_string_lossy()
+                .to_string()
+        })
+        .collect();
+    let first_dir = &dirs[0];
+
+    let mut language_tests = Vec::new();
+    for language in language_dirs(root_dirs)? {
+        let test_name = format_ident!(
+            "{}_is_complete",
+            identifier(sanitize(&language), &language)?
+        );
+        language_tests.push(quote! {
+            #[test]
+            fn #test_name() {
+                let summary = super::#localizer_type::bundle_coverage(&dirs()).unwrap();
+                let coverage = &summary.languages[#language];
+                assert!(
+                    coverage.missing.is_empty(),
+                    "{} is missing {}",
+                    #language,
+                    fluent_localization_loader::fold_displayable(coverage.missing.iter(), ", ")
+                );
+            }
+        });
+    }
+
+    Ok(quote! {
+        #[cfg(test)]
+        mod localization_tests {
+            fn dirs() -> Vec<std::path::PathBuf> {
+                vec![#(std::path::PathBuf::from(#dirs)),*]
+            }
+
+            #[test]
+            fn holder_loads() {
+                fluent_localization_loader::LocalizationHolder::builder()
+                    .base_path(std::path::PathBuf::from(#first_dir))
+                    .build()
+                    .unwrap();
+            }
+
+            #[test]
+            fn default_bundle_complete() {
+                super::#localizer_type::validate_default_bundle_in(&dirs()).unwrap();
+            }
+
+            #(#language_tests)*
+        }
+    })
+}
+
+/// Enum of the languages that have a directory next to the default one, in any of the localization directories
+fn language_enum(
+    root_dirs: &[PathBuf],
+    options: &MacroOptions,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let languages = language_dirs(root_dirs)?;
+
+    let mut variants = Vec::new();
+    let mut tags = Vec::new();
+    let mut taken: HashMap<String, &str> = HashMap::new();
+    for language in &languages {
+        let variant = identifier(camel_case(&sanitize(language)), language)?;
+        if let Some(other) = taken.insert(variant.clone(), language) {
+            return Err(syn::Error::new(
+                proc_macro2::Span::call_site(),
+                format!("Language directories {other} and {language} both generate the Language variant {variant}"),
+            ));
+        }
+        variants.push(format_ident!("{variant}"));
+        tags.push(LitStr::new(language, proc_macro2::Span::call_site()));
+    }
+    let count = variants.len();
+
+    Ok(quote! {
+        /// Every language with a localization directory when the bindings were generated
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        #vis enum Language {
+            #(#variants,)*
+        }
+
+        impl Language {
+            #vis const ALL: [Language; #count] = [#(Language::#variants,)*];
+
+            /// The name of the directory of the language
+            #vis fn as_str(&self) -> &'static str {
+                match *self {
+                    #(Language::#variants => #tags,)*
+                }
+            }
+        }
+
+        impl std::fmt::Display for Language {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for Language {
+            type Err = fluent_localization_loader::LocalizationLoadingError;
+
+            fn from_str(language: &str) -> Result<Self, Self::Err> {
+                match language {
+                    #(#tags => Ok(Language::#variants),)*
+                    _ => Err(fluent_localization_loader::LocalizationLoadingError::new(format!("Unknown language {language}"))),
+                }
+            }
+        }
+
+        impl <'a> #localizer_type<'a> {
+            #vis fn for_language(holder: &'a fluent_localization_loader::LocalizationHolder, language: Language) -> #localizer_type<'a> {
+                #localizer_type::new(holder, language.as_str())
+            }
+        }
+    })
+}
+
+/// Lookup of the kind of value every variable of a message expects, so callers can validate their arguments before localizing
+fn variable_kinds(
+    nodes_map: &HashMap<String, Node>,
+    options: &MacroOptions,
+) -> proc_macro2::TokenStream {
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let mut nodes: Vec<&Node> = nodes_map.values().filter(|node| !node.term).collect();
+    nodes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let names = nodes
+        .iter()
+        .map(|node| LitStr::new(&node.name, proc_macro2::Span::call_site()));
+    let kinds = nodes.iter().map(|node| {
+        let mut variables: Vec<&&str> = node.variables.iter().collect();
+        variables.sort_unstable();
+        let kinds = variables.into_iter().map(|variable| {
+            let kind = match node.variable_kind(variable).as_ref() {
+                Some(VariableKind::Number) => quote!(Number),
+                Some(VariableKind::String) => quote!(String),
+                Some(VariableKind::Unknown) | None => quote!(Unknown),
+            };
+            let variable = LitStr::new(variable, proc_macro2::Span::call_site());
+            quote!((#variable, fluent_localization_loader::VariableKind::#kind))
+        });
+        quote!(&[#(#kinds),*])
+    });
+
+    quote! {
+        impl <'a> #localizer_type<'a> {
+            /// The kind of value each variable of a message expects (like a number for `NUMBER($count)`), `None` if there is no such message
+            #vis fn expected_variable_kinds(name: &str) -> Option<&'static [(&'static str, fluent_localization_loader::VariableKind)]> {
+                match name {
+                    #(#names => Some(#kinds),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Lookup of the variables every message selects on with the keys of their variants, for tooling that offers them as choices
+fn select_variants(
+    nodes_map: &HashMap<String, Node>,
+    options: &MacroOptions,
+) -> proc_macro2::TokenStream {
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let mut nodes: Vec<&Node> = nodes_map
+        .values()
+        .filter(|node| !node.term && !node.selects.is_empty())
+        .collect();
+    nodes.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+    let names = nodes
+        .iter()
+        .map(|node| LitStr::new(&node.name, proc_macro2::Span::call_site()));
+    let selects = nodes.iter().map(|node| {
+        let selects = node.selects.iter().map(|(variable, keys)| {
+            let variable = LitStr::new(variable, proc_macro2::Span::call_site());
+            let keys = keys
+                .iter()
+                .map(|key| LitStr::new(key, proc_macro2::Span::call_site()));
+            quote!((#variable, &[#(#keys),*]))
+        });
+        quote!(&[#(#selects),*])
+    });
+
+    quote! {
+        impl <'a> #localizer_type<'a> {
+            /// The variables a message selects on with the keys of their variants (like `male`, `female` and `other` for `$gender`,
+            /// or the plural categories for a count), in the order they are used. Empty if there is no such message or it doesn't select on anything
+            #vis fn select_variants(name: &str) -> &'static [(&'static str, &'static [&'static str])] {
+                match name {
+                    #(#names => #selects,)*
+                    _ => &[],
+                }
+            }
+        }
+    }
+}
+
+/// Lookup of the lengths given with `# @max-length`, for UIs that validate translations against the space they have
+fn max_lengths(
+    nodes_map: &HashMap<String, Node>,
+    options: &MacroOptions,
+) -> proc_macro2::TokenStream {
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let mut nodes: Vec<(&Node, usize)> = nodes_map
+        .values()
+        .filter(|node| !node.term)
+        .filter_map(|node| Some((node, node.max_length()?)))
+        .collect();
+    nodes.sort_unstable_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+
+    let names = nodes
+        .iter()
+        .map(|(node, _)| LitStr::new(&node.name, proc_macro2::Span::call_site()));
+    let lengths = nodes.iter().map(|(_, max_length)| max_length);
+
+    quote! {
+        impl <'a> #localizer_type<'a> {
+            /// The maximum length of a message from `# @max-length: 40` in its comment, which also holds for its attributes.
+            /// `None` if there is no such message or it has no maximum length
+            #vis fn max_length(name: &str) -> Option<usize> {
+                match name {
+                    #(#names => Some(#lengths),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Loading of only the languages from the `languages` option, each guarded by the feature that enables it
+fn enabled_languages(options: &MacroOptions) -> proc_macro2::TokenStream {
+    if options.languages.is_empty() {
+        return quote!();
+    }
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+
+    let languages = options.languages.iter().map(|option| {
+        let language = &option.language;
+        match &option.feature {
+            Some(feature) => quote! {
+                #[cfg(feature = #feature)]
+                languages.push(#language);
+            },
+            None => quote!(languages.push(#language);),
+        }
+    });
+
+    quote! {
+        impl <'a> #localizer_type<'a> {
+            /// The languages from the `languages` option of the bindings whose feature is enabled
+            #vis fn enabled_languages() -> Vec<&'static str> {
+                let mut languages = Vec::new();
+                #(#languages)*
+                languages
+            }
+
+            /// Like `LocalizationHolder::load`, but only loads the enabled languages (and the default language, which is always loaded)
+            #vis fn load_enabled_languages() -> anyhow::Result<fluent_localization_loader::LocalizationHolder> {
+                Ok(fluent_localization_loader::LocalizationHolder::builder()
+                    .languages(&Self::enabled_languages())
+                    .build()?)
+            }
+        }
+    }
+}
+
+/// Struct holding the arguments of a message by name, and the `_with` helper taking it
+fn args_helper(
+    node: &Node,
+    options: &MacroOptions,
+) -> Result<
+    (
+        Option<String>,
+        proc_macro2::TokenStream,
+        proc_macro2::TokenStream,
+    ),
+    syn::Error,
+> {
+    let mut variables: Vec<&&str> = node.variables.iter().collect();
+    variables.sort_unstable_by_key(|value| value.to_lowercase());
+
+    let parameters = variables
+        .iter()
+        .map(|variable| identifier(sanitize(variable), variable))
+        .collect::<Result<Vec<String>, syn::Error>>()?;
+    let taken: HashSet<String> = parameters.iter().cloned().collect();
+    let generics: Vec<Ident> = get_generic_names(variables.len(), &taken)
+        .iter()
+        .map(|generic| format_ident!("{generic}"))
+        .collect();
+    let fields = parameters
+        .iter()
+        .map(|parameter| syn::parse_str::<Ident>(parameter))
+        .collect::<Result<Vec<Ident>, syn::Error>>()?;
+    let keys: Vec<LitStr> = variables
+        .iter()
+        .map(|variable| LitStr::new(variable, proc_macro2::Span::call_site()))
+        .collect();
+    let types = variables
+        .iter()
+        .map(|variable| syn::parse_str::<syn::Type>(node.value_type(variable)))
+        .collect::<Result<Vec<syn::Type>, syn::Error>>()?;
+
+    let struct_name = format_ident!(
+        "{}",
+        identifier(format!("{}Args", camel_case(&node.flat_name())), &node.name)?
+    );
+    let function_name = format_ident!(
+        "{}",
+        identifier(format!("{}_with", node.function_name(options)), &node.name)?
+    );
+    let name = LitStr::new(&node.name, proc_macro2::Span::call_site());
+    let localizer = match options.style {
+        Style::Flat => quote!(self),
+        Style::Nested => quote!(self.localizer),
+    };
+    let struct_doc = LitStr::new(
+        &format!(" Arguments for the {} message", node.name),
+        proc_macro2::Span::call_site(),
+    );
+    let docs = node.helper_attributes();
+    let vis = &options.vis;
+
+    let args_struct = quote! {
+        #[doc = #struct_doc]
+        #vis struct #struct_name<'a> {
+            #(#vis #fields: fluent_bundle::FluentValue<'a>,)*
+        }
+
+        impl <'a> #struct_name<'a> {
+            #[allow(clippy::too_many_arguments)]
+            #vis fn new<#(#generics),*>(#(#fields: #generics),*) -> Self
+            where
+                #(#generics: Into<#types>,)*
+            {
+                #struct_name {
+                    #(#fields: fluent_bundle::FluentValue::from(Into::<#types>::into(#fields)),)*
+                }
+            }
+
+            #vis fn into_fluent_args(self) -> fluent_bundle::FluentArgs<'a> {
+                let mut fluent_arguments = fluent_bundle::FluentArgs::new();
+                #(fluent_arguments.set(#keys, self.#fields);)*
+                fluent_arguments
+            }
+        }
+    };
+    let mut helper = quote! {
+        #docs
+        #vis fn #function_name(&self, arguments: #struct_name<'a>) -> std::borrow::Cow<'a, str> {
+            #localizer.localize(#name, Some(arguments.into_fluent_args()))
+        }
+    };
+    if options.fallible {
+        let try_function_name = format_ident!(
+            "{}",
+            identifier(
+                format!("try_{}_with", node.function_name(options)),
+                &node.name
+            )?
+        );
+        helper.extend(quote! {
+            #docs
+            #vis fn #try_function_name(&self, arguments: #struct_name<'a>) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+                #localizer.try_localize(#name, Some(arguments.into_fluent_args()))
+            }
+        });
+    }
+
+    Ok((node.owner(options.style), helper, args_struct))
+}
+
+/// Builder that sets the arguments of a message one at a time, and the `_args` helper creating it
+fn builder_helper(
+    node: &Node,
+    options: &MacroOptions,
+) -> Result<
+    (
+        Option<String>,
+        proc_macro2::TokenStream,
+        proc_macro2::TokenStream,
+    ),
+    syn::Error,
+> {
+    let mut variables: Vec<&&str> = node.variables.iter().collect();
+    variables.sort_unstable_by_key(|value| value.to_lowercase());
+
+    // Prefixed with `set_`, so these don't need escaping like the parameters do
+    let setters: Vec<Ident> = variables
+        .iter()
+        .map(|variable| format_ident!("set_{}", sanitize(variable)))
+        .collect();
+    let keys: Vec<LitStr> = variables
+        .iter()
+        .map(|variable| LitStr::new(variable, proc_macro2::Span::call_site()))
+        .collect();
+    let types = variables
+        .iter()
+        .map(|variable| syn::parse_str::<syn::Type>(node.value_type(variable)))
+        .collect::<Result<Vec<syn::Type>, syn::Error>>()?;
+
+    let struct_name = format_ident!(
+        "{}",
+        identifier(
+            format!("{}Builder", camel_case(&node.flat_name())),
+            &node.name
+        )?
+    );
+    let function_name = format_ident!(
+        "{}",
+        identifier(format!("{}_args", node.function_name(options)), &node.name)?
+    );
+    let name = LitStr::new(&node.name, proc_macro2::Span::call_site());
+    // Nested helpers hand out the localizer their category struct borrows, rather than borrowing the category struct itself
+    let (localizer, borrow) = match options.style {
+        Style::Flat => (quote!(self), quote!('_)),
+        Style::Nested => (quote!(self.localizer), quote!('l)),
+    };
+    let struct_doc = LitStr::new(
+        &format!(" Builder for the arguments of the {} message", node.name),
+        proc_macro2::Span::call_site(),
+    );
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let try_format = options.fallible.then(|| {
+        quote! {
+            #vis fn try_format(&self) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+                self.localizer.try_localize_borrowed_args(#name, Some(&self.arguments))
+            }
+        }
+    });
+    let docs = node.helper_attributes();
+
+    let builder = quote! {
+        #[doc = #struct_doc]
+        #vis struct #struct_name<'l, 'a> {
+            localizer: &'l #localizer_type<'a>,
+            arguments: fluent_bundle::FluentArgs<'a>,
+        }
+
+        impl <'l, 'a> #struct_name<'l, 'a> {
+            #(
+                #vis fn #setters(&mut self, value: impl Into<#types>) -> &mut Self {
+                    self.arguments.set(#keys, fluent_bundle::FluentValue::from(Into::<#types>::into(value)));
+                    self
+                }
+            )*
+
+            /// Localize the message with the arguments set so far
+            #vis fn format(&self) -> std::borrow::Cow<'a, str> {
+                self.localizer.localize_borrowed_args(#name, Some(&self.arguments))
+            }
+
+            #try_format
+        }
+    };
+    let helper = quote! {
+        #docs
+        #vis fn #function_name(&self) -> #struct_name<#borrow, 'a> {
+            #struct_name {
+                localizer: #localizer,
+                arguments: fluent_bundle::FluentArgs::new(),
+            }
+        }
+    };
+
+    Ok((node.owner(options.style), helper, builder))
+}
+
+/// `mod_log` to `ModLog`, for type names
+fn camel_case(sanitized: &str) -> String {
+    sanitized
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| part[..1].to_uppercase() + &part[1..])
+        .collect()
+}
+
+/// Put the helpers on the localizer, or on the struct of their category for the nested style
+fn assemble_helpers(
+    helpers: Vec<(Option<String>, proc_macro2::TokenStream)>,
+    options: &MacroOptions,
+) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let vis = &options.vis;
+    let localizer_type = &options.struct_name;
+    let mut flat = Vec::new();
+    let mut categories: BTreeMap<String, Vec<proc_macro2::TokenStream>> = BTreeMap::new();
+    for (owner, helper) in helpers {
+        match owner {
+            Some(category) => categories.entry(category).or_default().push(helper),
+            None => flat.push(helper),
+        }
+    }
+
+    let mut code = quote! {
+        impl <'a> #localizer_type<'a> {
+            #(#flat)*
+        }
+    };
+
+    for (category, helpers) in categories {
+        let struct_name = format_ident!(
+            "{}",
+            identifier(format!("{}Localizer", camel_case(&category)), &category)?
+        );
+        let accessor = syn::parse_str::<Ident>(&identifier(category.clone(), &category)?)?;
+        let doc = LitStr::new(
+            &format!(" Localizations from the {category} files"),
+            proc_macro2::Span::call_site(),
+        );
+
+        code.extend(quote! {
+            #[doc = #doc]
+            #vis struct #struct_name<'l, 'a> {
+                localizer: &'l #localizer_type<'a>,
+            }
+
+            impl <'a> #localizer_type<'a> {
+                #[doc = #doc]
+                #vis fn #accessor(&self) -> #struct_name<'_, 'a> {
+                    #struct_name { localizer: self }
+                }
+            }
+
+            impl <'l, 'a> #struct_name<'l, 'a> {
+                #(#helpers)*
+            }
+        });
+    }
+
+    Ok(code)
+}
+
+/// Declaration of a helper on the `Localizer` trait, its implementation calling the helper on the localizer, and the one for the owned
+/// localizer calling it on the localizer it hands out. Both borrow for as long as the localizer the trait is implemented for is borrowed
+#[allow(clippy::too_many_arguments)]
+fn trait_method(
+    node: &Node,
+    prefix: &str,
+    function_name: &Ident,
+    parameters: proc_macro2::TokenStream,
+    arguments: proc_macro2::TokenStream,
+    output: &proc_macro2::TokenStream,
+    docs: &proc_macro2::TokenStream,
+    options: &MacroOptions,
+) -> Result<TraitMethod, syn::Error> {
+    let localizer_type = &options.struct_name;
+    // Nested helpers are reached through their category, on the trait they need a name that is unique across categories
+    let (trait_name, helper) = match node.owner(options.style) {
+        Some(category) => {
+            let accessor = syn::parse_str::<Ident>(&identifier(category.clone(), &category)?)?;
+            (
+                format_ident!(
+                    "{}",
+                    identifier(format!("{prefix}{}", node.flat_name()), &node.name)?
+                ),
+                quote!(#accessor().#function_name),
+            )
+        }
+        None => (function_name.clone(), quote!(#function_name)),
+    };
+    let signature = quote!(fn #trait_name<'s>(&'s self, #parameters) -> #output);
+
+    let declaration = quote! {
+        #docs
+        #[allow(clippy::too_many_arguments)]
+        #signature;
+    };
+    // Attributes only go on the declaration, `#[deprecated]` is not allowed on trait implementations
+    let implementation = quote! {
+        #signature {
+            let localizer: &'s #localizer_type<'s> = self;
+            localizer.#helper(#arguments)
+        }
+    };
+    let owned_implementation = quote! {
+        #signature {
+            self.localizer().#helper(#arguments)
+        }
+    };
+    Ok((
+        trait_name.to_string(),
+        declaration,
+        implementation,
+        owned_implementation,
+    ))
+}
+
+/// Report every node with placeables nested deeper than `max_nesting` in a single error
+fn check_nesting(nodes_map: &HashMap<String, Node>, max_nesting: usize) -> Result<(), syn::Error> {
+    let mut nodes: Vec<&Node> = nodes_map.values().filter(|node| node.too_deep).collect();
+    if nodes.is_empty() {
+        return Ok(());
+    }
+    nodes.sort_unstable_by(|a, b| (a.source_file, &a.name).cmp(&(b.source_file, &b.name)));
+
+    Err(syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!(
+            "Found localizations nesting placeables more than {max_nesting} levels deep, raise max_nesting if this is intended:\n{}",
+            fold_displayable(
+                nodes
+                    .into_iter()
+                    .map(|node| format!("{} ({})", node.name, node.location())),
+                "\n"
+            )
+        ),
+    ))
+}
+
+/// Make sure every referenced message, term and attribute exists, listing all references that don't in a single error
+fn check_dangling(nodes_map: &HashMap<String, Node>) -> Result<(), syn::Error> {
+    let mut nodes: Vec<&Node> = nodes_map.values().collect();
+    nodes.sort_unstable_by(|a, b| (a.source_file, &a.name).cmp(&(b.source_file, &b.name)));
+
+    let mut dangling: Vec<String> = Vec::new();
+    for node in nodes {
+        let mut dependencies: Vec<&Dependency> = node.dependencies.iter().collect();
+        dependencies.sort_unstable();
+        for dependency in dependencies {
+            if nodes_map.contains_key(&dependency_key(*dependency)) {
+                continue;
+            }
+
+            // Messages with only attributes don't have a node of their own
+            let owner = dependency_key((dependency.0, dependency.1, None));
+            let has_attributes = nodes_map.keys().any(|key| {
+                key.strip_prefix(owner.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+            });
+            let problem = match dependency.2 {
+                Some(attribute) if nodes_map.contains_key(&owner) || has_attributes => {
+                    format!("attribute {attribute} of {owner}, which has no such attribute")
+                }
+                Some(attribute) => format!("{owner}.{attribute}, {owner} does not exist"),
+                None if has_attributes => format!("{owner}, which only has attributes"),
+                None => format!("{owner}, which does not exist"),
+            };
+            dangling.push(format!(
+                "{} ({}) references {problem}",
+                node.name,
+                node.location()
+            ));
+        }
+    }
+
+    if dangling.is_empty() {
+        return Ok(());
+    }
+
+    Err(syn::Error::new(
+        proc_macro2::Span::call_site(),
+        format!(
+            "Found references to localizations that don't exist:\n{}",
+            fold_displayable(dangling.into_iter(), "\n")
+        ),
+    ))
+}
+
+/// Make sure the variables annotated in comments are used by the message and names given with `@rust-name` are usable,
+/// the comment is shared by the value and attributes of a message
+fn check_annotations(nodes_map: &HashMap<String, Node>) -> Result<(), syn::Error> {
+    let mut nodes: Vec<&Node> = nodes_map.values().collect();
+    nodes.sort_unstable_by(|a, b| (a.source_file, &a.name).cmp(&(b.source_file, &b.name)));
+
+    let mut errors: Vec<String> = Vec::new();
+    for node in nodes.iter().filter(|node| !node.attribute) {
+        if let Some(rust_name) = node.rust_name {
+            let name = rust_name.trim_start_matches("::");
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            {
+                errors.push(format!(
+                    "The comment of {} ({}) gives it the rust name \"{rust_name}\", but that is not a snake case identifier",
+                    node.name,
+                    node.location()
+                ));
+            }
+        }
+
+        if let Some(max_length) = node.max_length {
+            if node.max_length().is_none() {
+                errors.push(format!(
+                    "The comment of {} ({}) gives it the max length \"{max_length}\", but that is not a number",
+                    node.name,
+                    node.location()
+                ));
+            }
+        }
+
+        let mut annotated: Vec<&&str> = node.annotated_kinds.keys().collect();
+        annotated.sort_unstable();
+        for variable in annotated {
+            let used = nodes.iter().any(|other| {
+                other.term == node.term
+                    && other.name.split('.').next() == Some(node.name.as_str())
+                    && other.variables.contains(variable)
+            });
+            if !used {
+                errors.push(format!(
+                    "The comment of {} ({}) annotates ${variable}, but it doesn't use that variable",
+                    node.name,
+                    node.location()
+                ));
+            }
+        }
+    }
+
+    errors
+        .into_iter()
+        .map(|error| syn::Error::new(proc_macro2::Span::call_site(), error))
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+        .map_or(Ok(()), Err)
+}
+
+/// Make sure no chain of references leads back to where it started, reporting the full path of the first cycle found
+fn check_cycles(nodes_map: &HashMap<String, Node>) -> Result<(), syn::Error> {
+    // Sorted so the same cycle is always reported the same way
+    let mut names: Vec<&String> = nodes_map.keys().collect();
+    names.sort_unstable();
+    let dependencies: HashMap<&str, Vec<String>> = nodes_map
+        .iter()
+        .map(|(name, node)| {
+            let mut dependencies: Vec<String> = node
+                .dependencies
+                .iter()
+                .map(|dependency| dependency_key(*dependency))
+                .collect();
+            dependencies.sort_unstable();
+            (name.as_str(), dependencies)
+        })
+        .collect();
+
+    let mut done: HashSet<&str> = HashSet::new();
+    for start in names {
+        if done.contains(start.as_str()) {
+            continue;
+        }
+
+        // Iterative depth first search, the stack holds the path from the start with the index of the next dependency to visit
+        let mut stack: Vec<(&str, usize)> = vec![(start.as_str(), 0)];
+        while let Some((name, index)) = stack.last_mut() {
+            let Some(next) = dependencies[*name].get(*index) else {
+                done.insert(*name);
+                stack.pop();
+                continue;
+            };
+            *index += 1;
+
+            // Unknown dependencies were already reported
+            let Some((next, _)) = nodes_map.get_key_value(next.as_str()) else {
+                continue;
+            };
+            if done.contains(next.as_str()) {
+                continue;
+            }
+
+            if let Some(position) = stack.iter().position(|(name, _)| *name == next) {
+                let cycle = stack[position..]
+                    .iter()
+                    .map(|(name, _)| format!("{name} ({})", nodes_map[*name].location()))
+                    .chain(std::iter::once(next.to_string()));
+                return Err(syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!(
+                        "Cyclic localization references: {}",
+                        fold_displayable(cycle, " → ")
+                    ),
+                ));
+            }
+            stack.push((next.as_str(), 0));
+        }
+    }
+
+    Ok(())
+}
+
+/// Make sure no two nodes generate the same helper and no two variables of a node the same parameter
+fn check_collisions(
+    nodes_map: &HashMap<String, Node>,
+    options: &MacroOptions,
+) -> Result<(), syn::Error> {
+    let style = options.style;
+    // Term attributes and messages marked with `@no-binding` don't get helpers
+    let mut nodes: Vec<&Node> = nodes_map
+        .values()
+        .filter(|node| !(node.no_binding || node.term && node.attribute))
+        .collect();
+    nodes.sort_unstable_by(|a, b| (a.source_file, &a.name).cmp(&(b.source_file, &b.name)));
+
+    let mut errors: Vec<String> = Vec::new();
+    let mut functions: HashMap<String, &Node> = HashMap::new();
+    let mut types: HashMap<String, &Node> = HashMap::new();
+    for node in nodes {
+        let function_name = node.function_name(options);
+        let with_args = (options.args && !node.term && !node.variables.is_empty())
+            .then(|| format!("{function_name}_with"));
+        let builder_args = (options.builder_args && !node.term && !node.variables.is_empty())
+            .then(|| format!("{function_name}_args"));
+        let mut function_names: Vec<String> =
+            Some(function_name).into_iter().chain(with_args).collect();
+        if options.fallible && !node.term {
+            let fallible: Vec<String> = function_names
+                .iter()
+                .map(|name| format!("try_{name}"))
+                .collect();
+            function_names.extend(fallible);
+        }
+        function_names.extend(builder_args);
+        for function_name in function_names {
+            let function_name = match node.owner(style) {
+                Some(owner) => format!("{owner}().{function_name}"),
+                None => function_name,
+            };
+            if let Some(other) = functions.insert(function_name.clone(), node) {
+                let hint = if other.rust_name.is_some() || node.rust_name.is_some() {
+                    ", change the name given with @rust-name"
+                } else if options.prefix || style == Style::Nested {
+                    ""
+                } else {
+                    ", keys need to be unique across all files when the category prefix is disabled"
                 };
-                (message.id.name, pattern, false)
+                errors.push(format!(
+                    "Localization keys \"{}\" ({}) and \"{}\" ({}) both generate the function {function_name}{hint}",
+                    other.name,
+                    other.location(),
+                    node.name,
+                    node.location()
+                ));
+            }
+        }
+
+        // Names from `@rust-name` that leave out the category can also clash with the generated types of other keys
+        if (options.args || options.builder_args) && !node.term && !node.variables.is_empty() {
+            let suffix = if options.args { "Args" } else { "Builder" };
+            let type_name = format!("{}{suffix}", camel_case(&node.flat_name()));
+            if let Some(other) = types.insert(type_name.clone(), node) {
+                errors.push(format!(
+                    "Localization keys \"{}\" ({}) and \"{}\" ({}) both generate the type {type_name}, change the name given with @rust-name",
+                    other.name,
+                    other.location(),
+                    node.name,
+                    node.location()
+                ));
+            }
+        }
+
+        let mut variables: Vec<&&str> = node.variables.iter().collect();
+        variables.sort_unstable();
+        let mut parameters: HashMap<String, &str> = HashMap::new();
+        for variable in variables {
+            if let Some(other) = parameters.insert(sanitize(variable), variable) {
+                errors.push(format!(
+                    "Variables ${other} and ${variable} of localization key \"{}\" ({}) both generate the parameter {}",
+                    node.name,
+                    node.location(),
+                    sanitize(variable)
+                ));
+            }
+        }
+    }
+
+    errors
+        .into_iter()
+        .map(|error| syn::Error::new(proc_macro2::Span::call_site(), error))
+        .reduce(|mut combined, error| {
+            combined.combine(error);
+            combined
+        })
+        .map_or(Ok(()), Err)
+}
+
+/// Normalize (part of) a name for use in an identifier. Non-ascii characters are transliterated where possible (`café` becomes `cafe`),
+/// anything else that isn't valid in a rust identifier becomes an underscore, with runs of underscores collapsed into one
+fn sanitize(original: &str) -> String {
+    let mut sanitized = String::with_capacity(original.len());
+    for c in deunicode(original).to_lowercase().chars() {
+        let c = if c.is_ascii_alphanumeric() { c } else { '_' };
+        if c != '_' || !sanitized.ends_with('_') {
+            sanitized.push(c);
+        }
+    }
+    sanitized
+}
+
+/// Turn a sanitized name into a valid identifier, prefixing leading digits and escaping keywords.
+/// `original` is only used to point at the offending key when no identifier can be made
+fn identifier(sanitized: String, original: &str) -> Result<String, syn::Error> {
+    let Some(first) = sanitized.chars().next() else {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Localization key \"{original}\" does not produce a valid rust identifier"),
+        ));
+    };
+
+    Ok(if first.is_ascii_digit() {
+        format!("_{sanitized}")
+    } else if UNESCAPABLE_KEYWORDS.contains(&sanitized.as_str()) {
+        format!("{sanitized}_")
+    } else if KEYWORDS.contains(&sanitized.as_str()) {
+        format!("r#{sanitized}")
+    } else {
+        sanitized
+    })
+}
+
+/// Generic parameter names `P1` to `Pn`, underscores get prepended in the unlikely case that would clash with a parameter name
+fn get_generic_names(amount: usize, taken: &HashSet<String>) -> Vec<String> {
+    let mut prefix = String::from("P");
+    while (1..=amount).any(|count| taken.contains(&format!("{prefix}{count}"))) {
+        prefix.insert(0, '_');
+    }
+    (1..=amount)
+        .map(|count| format!("{prefix}{count}"))
+        .collect()
+}
+
+fn generate_nodes_for<'a>(
+    resource: &'a Resource,
+    ast: &'a ast::Resource<&'a str>,
+    max_nesting: usize,
+) -> Vec<Node<'a>> {
+    let mut out = Vec::new();
+
+    for entry in &ast.body {
+        // The value and every attribute of a message get their own node, as (key, identifier, pattern, attribute, stripped entry)
+        let (parts, term, comment) = match entry {
+            Entry::Message(message) => {
+                let value = message.value.as_ref().map(|pattern| {
+                    let stripped = Entry::Message(ast::Message {
+                        id: message.id.clone(),
+                        value: Some(pattern.clone()),
+                        attributes: Vec::new(),
+                        comment: None,
+                    });
+                    (
+                        message.id.name.to_string(),
+                        message.id.name,
+                        pattern,
+                        false,
+                        stripped,
+                    )
+                });
+                let attributes = message.attributes.iter().map(|attribute| {
+                    let stripped = Entry::Message(ast::Message {
+                        id: message.id.clone(),
+                        value: None,
+                        attributes: vec![attribute.clone()],
+                        comment: None,
+                    });
+                    (
+                        format!("{}.{}", message.id.name, attribute.id.name),
+                        attribute.id.name,
+                        &attribute.value,
+                        true,
+                        stripped,
+                    )
+                });
+                (
+                    value.into_iter().chain(attributes).collect::<Vec<_>>(),
+                    false,
+                    &message.comment,
+                )
+            }
+            Entry::Term(term) => {
+                let stripped = Entry::Term(ast::Term {
+                    id: term.id.clone(),
+                    value: term.value.clone(),
+                    attributes: Vec::new(),
+                    comment: None,
+                });
+                // Term attributes don't get helpers, but messages can select on them so their variables are still needed
+                let attributes = term.attributes.iter().map(|attribute| {
+                    let stripped = Entry::Term(ast::Term {
+                        id: term.id.clone(),
+                        value: term.value.clone(),
+                        attributes: vec![attribute.clone()],
+                        comment: None,
+                    });
+                    (
+                        format!("{}.{}", term.id.name, attribute.id.name),
+                        attribute.id.name,
+                        &attribute.value,
+                        true,
+                        stripped,
+                    )
+                });
+                (
+                    std::iter::once((
+                        term.id.name.to_string(),
+                        term.id.name,
+                        &term.value,
+                        false,
+                        stripped,
+                    ))
+                    .chain(attributes)
+                    .collect(),
+                    true,
+                    &term.comment,
+                )
             }
-            Entry::Term(term) => (term.id.name, &term.value, true),
             _ => continue,
         };
 
-        let mut node = Node::new(parrent, name, term);
-        process_pattern_elements(&pattern.elements, &mut node);
-        out.push(node)
+        for (name, id, pattern, attribute, stripped) in parts {
+            let mut node = Node::new(&resource.name, &resource.file_name, name, term);
+            node.attribute = attribute;
+            node.source_line = line_of(resource.resource.source(), id);
+            for directive in comment_directives(comment) {
+                // `@deprecated` can be followed by a note, `# @deprecated: use new-key instead`
+                if let Some(note) = directive.strip_prefix("deprecated") {
+                    if note.is_empty() || note.starts_with([':', ' ', '\t']) {
+                        node.deprecated = Some(note.trim_start_matches(':').trim());
+                        continue;
+                    }
+                }
+                // `# @rust-name: welcome_returning_user` overrides the name of the helpers
+                if let Some(name) = directive.strip_prefix("rust-name") {
+                    if name.starts_with([':', ' ', '\t']) {
+                        node.rust_name = Some(name.trim_start_matches(':').trim());
+                        continue;
+                    }
+                }
+                // `# @max-length: 40` limits how long the text may be, `# @context: button label` says where it ends up
+                if let Some(max_length) = directive.strip_prefix("max-length") {
+                    if max_length.starts_with([':', ' ', '\t']) {
+                        node.max_length = Some(max_length.trim_start_matches(':').trim());
+                        continue;
+                    }
+                }
+                if let Some(context) = directive.strip_prefix("context") {
+                    if context.starts_with([':', ' ', '\t']) {
+                        node.context = Some(context.trim_start_matches(':').trim());
+                        continue;
+                    }
+                }
+                match directive {
+                    "no-fallback" if !term => node.no_fallback = true,
+                    "no-binding" if !term => node.no_binding = true,
+                    _ => (),
+                }
+            }
+            node.annotated_kinds = comment_annotations(comment).collect();
+            node.comment = comment
+                .iter()
+                .flat_map(|comment| comment.content.iter())
+                .copied()
+                .filter(|line| !line.trim().starts_with('@'))
+                .collect();
+            node.source_text = serializer::serialize(&ast::Resource {
+                body: vec![stripped],
+            })
+            .trim_end()
+            .to_string();
+            process_pattern_elements(&pattern.elements, &mut node, max_nesting);
+            out.push(node)
+        }
     }
 
     out
 }
 
-fn process_pattern_elements<'a>(attributes: &'a Vec<PatternElement<&'a str>>, node: &mut Node<'a>) {
+/// Line (starting at 1) `part` starts on, `part` has to be a slice of `source` as the parsed AST borrows from it
+fn line_of(source: &str, part: &str) -> Option<usize> {
+    let offset = (part.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    let before = source.get(..offset)?;
+    Some(before.matches('\n').count() + 1)
+}
+
+/// Directives (`# @directive`) in the comment attached to an entry, without the `@`
+fn comment_directives<'a>(comment: &'a Option<Comment<&'a str>>) -> impl Iterator<Item = &'a str> {
+    comment
+        .iter()
+        .flat_map(|comment| comment.content.iter())
+        .filter_map(|line| line.trim().strip_prefix('@'))
+        .map(str::trim)
+}
+
+/// Variable types annotated in the comment attached to an entry, following the `# $name (Type) - description` convention.
+/// Only `Number` and `String` change the generated helpers, other types are left to the documentation
+fn comment_annotations<'a>(
+    comment: &'a Option<Comment<&'a str>>,
+) -> impl Iterator<Item = (&'a str, VariableKind)> {
+    comment
+        .iter()
+        .flat_map(|comment| comment.content.iter())
+        .filter_map(|line| {
+            let (name, rest) = line.trim().strip_prefix('$')?.split_once('(')?;
+            let (kind, _) = rest.split_once(')')?;
+            let kind = match kind.trim() {
+                "Number" => VariableKind::Number,
+                "String" => VariableKind::String,
+                _ => return None,
+            };
+            Some((name.trim(), kind))
+        })
+}
+
+/// `depth` is how many more levels of placeables may be nested, so pathological nesting can't overflow the stack
+fn process_pattern_elements<'a>(
+    attributes: &'a Vec<PatternElement<&'a str>>,
+    node: &mut Node<'a>,
+    depth: usize,
+) {
     for attribute in attributes {
         // We only care about placables since those are dynamic, we are not interested in fixed textelements
         match attribute {
             PatternElement::TextElement { value: _ } => (),
             PatternElement::Placeable { expression } => {
-                process_expression(expression, node);
+                process_expression(expression, node, depth);
             }
         }
     }
 }
 
-fn process_expression<'a>(expression: &'a Expression<&'a str>, node: &mut Node<'a>) {
+fn process_expression<'a>(expression: &'a Expression<&'a str>, node: &mut Node<'a>, depth: usize) {
+    let Some(depth) = depth.checked_sub(1) else {
+        node.too_deep = true;
+        return;
+    };
+
     match expression {
         Expression::Select { selector, variants } => {
-            process_inline_expression(selector, node);
+            if let InlineExpression::VariableReference { id } = selector {
+                let numeric = variants.iter().any(|variant| match variant.key {
+                    VariantKey::NumberLiteral { .. } => true,
+                    VariantKey::Identifier { name } => PLURAL_CATEGORIES.contains(&name),
+                });
+                if numeric {
+                    node.expect_kind(id.name, VariableKind::Number);
+                } else if variants.iter().any(
+                    |variant| !matches!(variant.key, VariantKey::Identifier { name } if name == "other"),
+                ) {
+                    node.expect_kind(id.name, VariableKind::String);
+                }
+            }
+            let selected = match selector {
+                InlineExpression::VariableReference { id } => Some(id.name),
+                InlineExpression::FunctionReference { arguments, .. } => {
+                    match arguments.positional.first() {
+                        Some(InlineExpression::VariableReference { id }) => Some(id.name),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some(variable) = selected {
+                node.select_on(
+                    variable,
+                    variants.iter().map(|variant| match variant.key {
+                        VariantKey::Identifier { name } => name,
+                        VariantKey::NumberLiteral { value } => value,
+                    }),
+                );
+            }
+            process_inline_expression(selector, node, depth);
             for variant in variants {
-                process_pattern_elements(&variant.value.elements, node)
+                process_pattern_elements(&variant.value.elements, node, depth)
             }
         }
-        Expression::Inline(inline) => process_inline_expression(inline, node),
+        Expression::Inline(inline) => process_inline_expression(inline, node, depth),
     }
 }
 
-fn process_inline_expression<'a>(expression: &'a InlineExpression<&'a str>, node: &mut Node<'a>) {
+fn process_inline_expression<'a>(
+    expression: &'a InlineExpression<&'a str>,
+    node: &mut Node<'a>,
+    depth: usize,
+) {
     match expression {
-        InlineExpression::FunctionReference {
-            id: _,
-            arguments: _,
-        } => todo!(), // leaving this as a crash intentionally for now since i don't know how these work exactly yet. will deal with this if i ever end up actually using them
-        InlineExpression::MessageReference { id, attribute: _ } => {
-            node.dependencies.insert(id.name);
+        InlineExpression::FunctionReference { id, arguments } => {
+            // Functions are resolved at runtime, we only care about what variables get passed into them
+            if id.name == "NUMBER" {
+                if let Some(InlineExpression::VariableReference { id }) =
+                    arguments.positional.first()
+                {
+                    node.expect_kind(id.name, VariableKind::Number);
+                }
+            }
+            process_call_arguments(arguments, node, depth);
+        }
+        InlineExpression::MessageReference { id, attribute } => {
+            node.dependencies.insert((
+                false,
+                id.name,
+                attribute.as_ref().map(|attribute| attribute.name),
+            ));
         }
         InlineExpression::TermReference {
             id,
-            attribute: _,
-            arguments: _,
+            attribute,
+            arguments,
         } => {
-            node.dependencies.insert(id.name);
+            // Fluent gives every term reference its own scope with only the named arguments in it, so the variables of the term
+            // are never needed by this node. Variables used in the arguments are: the term ignores positional ones, but they are
+            // still resolved (and fail without a value) in the scope of this node
+            node.dependencies.insert((
+                true,
+                id.name,
+                attribute.as_ref().map(|attribute| attribute.name),
+            ));
+            for argument in arguments.iter().flat_map(|arguments| {
+                arguments
+                    .positional
+                    .iter()
+                    .chain(arguments.named.iter().map(|named| &named.value))
+            }) {
+                process_inline_expression(argument, node, depth);
+            }
         }
         InlineExpression::VariableReference { id } => {
             node.variables.insert(id.name);
         }
         InlineExpression::Placeable { expression } => {
-            process_expression(expression, node);
+            process_expression(expression, node, depth);
         }
         InlineExpression::StringLiteral { value: _ }
         | InlineExpression::NumberLiteral { value: _ } => {}
@@ -377,3 +2729,17 @@ fn process_inline_expression<'a>(expression: &'a InlineExpression<&'a str>, node
         node.variables.insert(id.name);
     }
 }
+
+fn process_call_arguments<'a>(
+    arguments: &'a CallArguments<&'a str>,
+    node: &mut Node<'a>,
+    depth: usize,
+) {
+    for argument in arguments
+        .positional
+        .iter()
+        .chain(arguments.named.iter().map(|named| &named.value))
+    {
+        process_inline_expression(argument, node, depth);
+    }
+}
@@ -0,0 +1,238 @@
+use std::{env, path::PathBuf};
+
+use quote::format_ident;
+use syn::{
+    braced, bracketed,
+    ext::IdentExt,
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_quote, Ident, LitBool, LitInt, LitStr, Token, Visibility,
+};
+
+/// How the helpers are laid out on the localizer
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Style {
+    /// Every helper directly on the localizer, prefixed with its category: `localizer.moderation_ban_reason()`
+    #[default]
+    Flat,
+    /// An accessor per category that holds its helpers: `localizer.moderation().ban_reason()`
+    Nested,
+}
+
+/// A language listed in the `languages` option, with the cargo feature that enables it (`None` if it is always enabled)
+pub struct LanguageOption {
+    pub language: LitStr,
+    pub feature: Option<LitStr>,
+}
+
+impl Parse for LanguageOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let language = input.parse::<LitStr>()?;
+        input.parse::<Token![=>]>()?;
+        let condition = Ident::parse_any(input)?;
+        let feature = match condition.to_string().as_str() {
+            "always" => None,
+            "feature" => {
+                let content;
+                parenthesized!(content in input);
+                Some(content.parse::<LitStr>()?)
+            }
+            other => {
+                return Err(syn::Error::new(
+                    condition.span(),
+                    format!(
+                        "Unknown language condition {other}, expected always or feature(\"name\")"
+                    ),
+                ))
+            }
+        };
+
+        Ok(LanguageOption { language, feature })
+    }
+}
+
+const DEFAULT_STRUCT_NAME: &str = "LanguageLocalizer";
+const DUMP_VARIABLE: &str = "FLUENT_BINDGEN_DUMP";
+const DEFAULT_MAX_NESTING: usize = 32;
+
+/// Options passed to the macro as `key = value` pairs, for example `bind_localizations!(terms = true)`
+pub struct MacroOptions {
+    /// Also generate `term_*` helpers to render terms directly, for tooling that wants to preview them
+    pub terms: bool,
+    pub style: Style,
+    /// Also generate an `{Name}Args` struct per message with variables, and a `{helper}_with` helper that takes it
+    pub args: bool,
+    /// Prefix flat helpers with their category, `errors_not_found` rather than `not_found`
+    pub prefix: bool,
+    /// Also generate an `{helper}_args` helper per message with variables, returning a builder to set them one at a time
+    pub builder_args: bool,
+    /// Also generate a `try_{helper}` per helper that returns a `LocalizeError` instead of falling back
+    pub fallible: bool,
+    /// Languages to load, each either always or only when a cargo feature is enabled. All languages are loaded if empty
+    pub languages: Vec<LanguageOption>,
+    /// Localization directories to generate bindings for, later ones override the messages of earlier ones.
+    /// Only the directory from `TRANSLATION_DIR` (or `localizations`) if empty
+    pub dirs: Vec<LitStr>,
+    /// Names of files in the default directory that don't get bindings, on top of the ones starting with `_`
+    pub exclude: Vec<LitStr>,
+    /// Visibility of everything that is generated, `pub` by default
+    pub vis: Visibility,
+    /// Name of the localizer struct, `LanguageLocalizer` by default. The owned localizer gets `Owned` in front of it
+    pub struct_name: Ident,
+    /// Module to generate everything in, with the localizer re-exported next to it, see [`MacroOptions::module`]
+    pub module: Option<Ident>,
+    /// File to write the generated code to for inspection, see [`MacroOptions::dump_path`]
+    pub dump: Option<LitStr>,
+    /// How deep placeables can be nested in a message before it is reported, guards against files that would overflow the stack
+    pub max_nesting: usize,
+    /// Also generate a `#[cfg(test)]` module that loads and validates the localizations, with a completeness test per language
+    pub generate_tests: bool,
+}
+
+impl Default for MacroOptions {
+    fn default() -> Self {
+        MacroOptions {
+            terms: false,
+            style: Style::default(),
+            args: false,
+            prefix: true,
+            builder_args: false,
+            fallible: false,
+            languages: Vec::new(),
+            dirs: Vec::new(),
+            exclude: Vec::new(),
+            vis: parse_quote!(pub),
+            struct_name: format_ident!("{DEFAULT_STRUCT_NAME}"),
+            module: None,
+            dump: None,
+            max_nesting: DEFAULT_MAX_NESTING,
+            generate_tests: false,
+        }
+    }
+}
+
+impl MacroOptions {
+    /// Where to write the generated code to, from the `dump` option or otherwise the `FLUENT_BINDGEN_DUMP` environment variable
+    pub fn dump_path(&self) -> Option<PathBuf> {
+        match &self.dump {
+            Some(dump) => Some(PathBuf::from(dump.value())),
+            None => env::var_os(DUMP_VARIABLE).map(PathBuf::from),
+        }
+    }
+
+    /// Module to generate everything in. Localizers with a custom name get a module named after them unless one is given (`audit_strings`
+    /// for `AuditStrings`), so invocations for different catalogs don't both generate `MESSAGES`, `MessageKey` and the like in the same scope.
+    /// The default localizer is generated in place
+    pub fn module(&self) -> Option<Ident> {
+        if self.module.is_some() || self.struct_name == DEFAULT_STRUCT_NAME {
+            return self.module.clone();
+        }
+
+        let mut module = String::new();
+        for (index, c) in self.struct_name.to_string().chars().enumerate() {
+            if c.is_uppercase() && index > 0 {
+                module.push('_');
+            }
+            module.extend(c.to_lowercase());
+        }
+        Some(format_ident!("{module}"))
+    }
+
+    /// Whether a file is left out of the bindings, either listed in `exclude` or starting with `_`
+    pub fn excludes(&self, file_name: &str) -> bool {
+        file_name.starts_with('_')
+            || self
+                .exclude
+                .iter()
+                .any(|excluded| excluded.value() == file_name)
+    }
+}
+
+impl Parse for MacroOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = MacroOptions::default();
+
+        while !input.is_empty() {
+            let key = Ident::parse_any(input)?;
+            input.parse::<Token![=]>()?;
+
+            match key.to_string().as_str() {
+                "terms" => options.terms = input.parse::<LitBool>()?.value,
+                "args" => options.args = input.parse::<LitBool>()?.value,
+                "prefix" => options.prefix = input.parse::<LitBool>()?.value,
+                "builder_args" => options.builder_args = input.parse::<LitBool>()?.value,
+                "fallible" => options.fallible = input.parse::<LitBool>()?.value,
+                "generate_tests" => options.generate_tests = input.parse::<LitBool>()?.value,
+                "languages" => {
+                    let content;
+                    braced!(content in input);
+                    options.languages = content
+                        .parse_terminated(LanguageOption::parse, Token![,])?
+                        .into_iter()
+                        .collect();
+                    let mut seen = std::collections::HashSet::new();
+                    if let Some(duplicate) = options
+                        .languages
+                        .iter()
+                        .find(|option| !seen.insert(option.language.value()))
+                    {
+                        return Err(syn::Error::new(
+                            duplicate.language.span(),
+                            format!(
+                                "Language {} is listed more than once",
+                                duplicate.language.value()
+                            ),
+                        ));
+                    }
+                }
+                "dirs" => {
+                    let content;
+                    bracketed!(content in input);
+                    options.dirs = content
+                        .parse_terminated(|input| input.parse::<LitStr>(), Token![,])?
+                        .into_iter()
+                        .collect();
+                }
+                "exclude" => {
+                    let content;
+                    bracketed!(content in input);
+                    options.exclude = content
+                        .parse_terminated(|input| input.parse::<LitStr>(), Token![,])?
+                        .into_iter()
+                        .collect();
+                }
+                "vis" => options.vis = input.parse::<Visibility>()?,
+                "max_nesting" => options.max_nesting = input.parse::<LitInt>()?.base10_parse()?,
+                "dump" => options.dump = Some(input.parse::<LitStr>()?),
+                "module" => options.module = Some(input.parse::<Ident>()?),
+                "struct" => options.struct_name = input.parse::<Ident>()?,
+                "style" => {
+                    let value = Ident::parse_any(input)?;
+                    options.style = match value.to_string().as_str() {
+                        "flat" => Style::Flat,
+                        "nested" => Style::Nested,
+                        other => {
+                            return Err(syn::Error::new(
+                                value.span(),
+                                format!("Unknown style {other}, expected flat or nested"),
+                            ))
+                        }
+                    };
+                }
+                other => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("Unknown bind_localizations option {other}"),
+                    ))
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(options)
+    }
+}
@@ -0,0 +1,626 @@
+use std::{env, path::Path};
+
+use syn::{
+    FnArg, GenericArgument, ImplItem, Item, Pat, PathArguments, TypeParamBound, WherePredicate,
+};
+
+use quote::ToTokens;
+
+use super::*;
+
+/// Expand the macro for a directory in `test-data`, with the options after `dirs`
+fn expand_case(case: &str, options: &str) -> proc_macro2::TokenStream {
+    expand_cases(&[case], options)
+}
+
+/// Expand the macro for several directories in `test-data` at once, later ones overriding earlier ones
+fn expand_cases(cases: &[&str], options: &str) -> proc_macro2::TokenStream {
+    let dirs: Vec<String> = cases
+        .iter()
+        .map(|case| {
+            let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("test-data")
+                .join(case);
+            format!("{:?}", dir.to_string_lossy())
+        })
+        .collect();
+    let options = format!("dirs = [{}], {options}", dirs.join(", "));
+    expand(syn::parse_str(&options).expect("the options parse"))
+}
+
+fn parse(code: proc_macro2::TokenStream) -> syn::File {
+    syn::parse2(code).expect("the generated code parses")
+}
+
+/// Messages of the `compile_error!`s in the generated code
+fn compile_errors(code: &proc_macro2::TokenStream) -> Vec<String> {
+    parse(code.clone())
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+            // `syn::Error` writes them as `::core::compile_error!`
+            Item::Macro(item)
+                if item
+                    .mac
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "compile_error") =>
+            {
+                Some(
+                    item.mac
+                        .parse_body::<LitStr>()
+                        .expect("the message is a string")
+                        .value(),
+                )
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// The only error the expansion failed with
+fn error(case: &str, options: &str) -> String {
+    let mut errors = compile_errors(&expand_case(case, options));
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    errors.remove(0)
+}
+
+/// Generated code that has to have expanded without errors
+fn generated(case: &str, options: &str) -> syn::File {
+    let code = expand_case(case, options);
+    let errors = compile_errors(&code);
+    assert!(errors.is_empty(), "{errors:?}");
+    parse(code)
+}
+
+/// Functions generated in inherent impls of the type, with the names of their parameters
+fn methods(file: &syn::File, type_name: &str) -> BTreeMap<String, Vec<String>> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item) if item.trait_.is_none() => Some(item),
+            _ => None,
+        })
+        .filter(|item| matches!(&*item.self_ty, syn::Type::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == type_name)))
+        .flat_map(|item| &item.items)
+        .filter_map(|item| match item {
+            ImplItem::Fn(function) => Some(function),
+            _ => None,
+        })
+        .map(|function| {
+            let parameters = function
+                .sig
+                .inputs
+                .iter()
+                .filter_map(|input| match input {
+                    FnArg::Typed(typed) => match &*typed.pat {
+                        Pat::Ident(ident) => Some(ident.ident.to_string()),
+                        _ => None,
+                    },
+                    FnArg::Receiver(_) => None,
+                })
+                .collect();
+            (function.sig.ident.to_string(), parameters)
+        })
+        .collect()
+}
+
+/// Parameters of a helper on the localizer
+fn parameters(file: &syn::File, helper: &str) -> Vec<String> {
+    methods(file, "LanguageLocalizer")
+        .remove(helper)
+        .unwrap_or_else(|| panic!("{helper} was not generated"))
+}
+
+/// The types the parameters of a helper on the localizer have to convert into, from the `P1: Into<T>` bounds
+fn bounds(file: &syn::File, helper: &str) -> Vec<String> {
+    let function = file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item) if item.trait_.is_none() => Some(item),
+            _ => None,
+        })
+        .flat_map(|item| &item.items)
+        .find_map(|item| match item {
+            ImplItem::Fn(function) if function.sig.ident == helper => Some(function),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("{helper} was not generated"));
+
+    function
+        .sig
+        .generics
+        .where_clause
+        .iter()
+        .flat_map(|clause| &clause.predicates)
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(predicate) => predicate.bounds.first(),
+            _ => None,
+        })
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => bound.path.segments.last(),
+            _ => None,
+        })
+        .filter_map(|into| match &into.arguments {
+            PathArguments::AngleBracketed(arguments) => arguments.args.first(),
+            _ => None,
+        })
+        .map(|argument| match argument {
+            GenericArgument::Type(value_type) => quote!(#value_type).to_string().replace(' ', ""),
+            _ => panic!("Into takes a type"),
+        })
+        .collect()
+}
+
+/// A function generated in an inherent impl of the localizer
+fn function(file: &syn::File, name: &str) -> syn::ImplItemFn {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(item) if item.trait_.is_none() => Some(item),
+            _ => None,
+        })
+        .flat_map(|item| &item.items)
+        .find_map(|item| match item {
+            ImplItem::Fn(function) if function.sig.ident == name => Some(function.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("{name} was not generated"))
+}
+
+/// Attributes of a helper on the localizer other than its documentation
+fn attributes(file: &syn::File, helper: &str) -> Vec<String> {
+    function(file, helper)
+        .attrs
+        .iter()
+        .filter(|attribute| !attribute.path().is_ident("doc"))
+        .map(|attribute| attribute.meta.to_token_stream().to_string())
+        .collect()
+}
+
+/// Documentation of a helper on the localizer, a line per `#[doc]`
+fn documentation(file: &syn::File, helper: &str) -> Vec<String> {
+    function(file, helper)
+        .attrs
+        .iter()
+        .filter_map(|attribute| match &attribute.meta {
+            syn::Meta::NameValue(doc) if doc.path.is_ident("doc") => match &doc.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(line),
+                    ..
+                }) => Some(line.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// Arms of the `match name` in a generated lookup function, with the key and what they return
+fn lookup(file: &syn::File, name: &str) -> Vec<(String, String)> {
+    let function = function(file, name);
+    let Some(syn::Stmt::Expr(syn::Expr::Match(lookup), _)) = function.block.stmts.first() else {
+        panic!("{name} matches on the name");
+    };
+    lookup
+        .arms
+        .iter()
+        .filter_map(|arm| match &arm.pat {
+            Pat::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(key),
+                ..
+            }) => Some((key.value(), arm.body.to_token_stream().to_string())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The strings of a generated constant array, like `MESSAGES`
+fn constant(file: &syn::File, name: &str) -> Vec<String> {
+    let list = file
+        .items
+        .iter()
+        .find_map(|item| match item {
+            Item::Const(item) if item.ident == name => Some(&item.expr),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("{name} was not generated"));
+    let syn::Expr::Array(list) = &**list else {
+        panic!("{name} is an array");
+    };
+    list.elems
+        .iter()
+        .map(|element| match element {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(key),
+                ..
+            }) => key.value(),
+            _ => panic!("{name} lists strings"),
+        })
+        .collect()
+}
+
+/// Notes of the warnings the expansion emits as deprecated constants
+fn warnings(file: &syn::File) -> Vec<String> {
+    file.items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Const(item) if item.ident == "_" => Some(item),
+            _ => None,
+        })
+        .filter_map(|item| match &*item.expr {
+            syn::Expr::Block(block) => block.block.stmts.first(),
+            _ => None,
+        })
+        .filter_map(|statement| match statement {
+            syn::Stmt::Item(Item::Const(warning)) => warning.attrs.first(),
+            _ => None,
+        })
+        .map(|deprecated| {
+            let syn::Meta::List(list) = &deprecated.meta else {
+                panic!("warnings are deprecated with a note");
+            };
+            let note: syn::MetaNameValue = list.parse_args().expect("the note parses");
+            match note.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(note),
+                    ..
+                }) => note.value(),
+                _ => panic!("the note is a string"),
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn function_references_pass_on_their_variables() {
+    let file = generated("functions", "");
+
+    assert_eq!(parameters(&file, "base_price"), ["amount"]);
+    assert_eq!(parameters(&file, "base_shout"), ["name", "other"]);
+}
+
+#[test]
+fn generic_names_step_around_parameters() {
+    assert_eq!(get_generic_names(2, &HashSet::new()), ["P1", "P2"]);
+
+    let taken = HashSet::from(["P2".to_string()]);
+    assert_eq!(get_generic_names(2, &taken), ["_P1", "_P2"]);
+    assert_eq!(get_generic_names(30, &taken).len(), 30);
+}
+
+#[test]
+fn colliding_names_are_reported_with_both_keys() {
+    let mut errors = compile_errors(&expand_case("collisions", ""));
+    errors.sort_unstable();
+
+    assert_eq!(
+        errors,
+        [
+            "Localization keys \"User-Name\" (base.ftl:3) and \"user-name\" (base.ftl:1) both generate the function base_user_name",
+            "Localization keys \"user-name\" (base.ftl:1) and \"user_name\" (base.ftl:2) both generate the function base_user_name",
+            "Variables $user-name and $user_name of localization key \"greeting\" (base.ftl:4) both generate the parameter user_name",
+        ]
+    );
+}
+
+#[test]
+fn dashed_file_names_prefix_every_helper_the_same() {
+    let file = generated("dashed", "");
+
+    assert_eq!(parameters(&file, "mod_log_joined"), ["user"]);
+    assert_eq!(parameters(&file, "mod_log_left"), Vec::<String>::new());
+}
+
+#[test]
+fn sanitize_transliterates_what_it_can() {
+    assert_eq!(sanitize("Café-menu"), "cafe_menu");
+    assert_eq!(sanitize("مرحبا"), "mrhb");
+    assert_eq!(sanitize("你好-world"), "ni_hao_world");
+    // Anything without an ascii equivalent becomes an underscore, runs of them collapse into one
+    assert_eq!(sanitize("a\u{e000}\u{e001}b"), "a_b");
+    assert_eq!(sanitize("a--b__c"), "a_b_c");
+}
+
+#[test]
+fn non_ascii_file_names_give_ascii_helpers() {
+    let file = generated("unicode", "");
+
+    assert_eq!(parameters(&file, "cafe_menu_welcome"), ["name"]);
+}
+
+#[test]
+fn helpers_can_leave_out_the_category() {
+    let prefixed = generated("categories", "");
+    assert_eq!(
+        parameters(&prefixed, "errors_not_found"),
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        parameters(&prefixed, "general_not_found"),
+        Vec::<String>::new()
+    );
+    assert_eq!(parameters(&prefixed, "general_welcome"), ["user"]);
+
+    let unprefixed = generated("dashed", "prefix = false");
+    assert_eq!(parameters(&unprefixed, "joined"), ["user"]);
+    assert_eq!(parameters(&unprefixed, "left"), Vec::<String>::new());
+}
+
+#[test]
+fn leaving_out_the_category_reports_keys_that_collide() {
+    assert_eq!(
+        error("categories", "prefix = false"),
+        "Localization keys \"not-found\" (errors.ftl:1) and \"not_found\" (general.ftl:1) both generate the function not_found, \
+         keys need to be unique across all files when the category prefix is disabled"
+    );
+}
+
+#[test]
+fn attribute_only_messages_get_helpers_for_their_attributes() {
+    let file = generated("attributes", "");
+    let helpers = methods(&file, "LanguageLocalizer");
+
+    assert_eq!(helpers["base_login_label"], Vec::<String>::new());
+    assert_eq!(helpers["base_login_tooltip"], ["provider"]);
+    assert!(!helpers.contains_key("base_login"));
+}
+
+#[test]
+fn terms_only_see_the_arguments_they_are_passed() {
+    let file = generated("terms", "");
+
+    assert_eq!(parameters(&file, "base_literal"), Vec::<String>::new());
+    assert_eq!(parameters(&file, "base_variable"), ["style"]);
+    assert_eq!(parameters(&file, "base_bare"), Vec::<String>::new());
+    // Messages do hand their variables to the messages referencing them
+    assert_eq!(parameters(&file, "base_nested"), ["other", "style", "user"]);
+}
+
+#[test]
+fn later_directories_override_whole_messages() {
+    let code = expand_cases(&["override/shared", "override/app"], "");
+    let errors = compile_errors(&code);
+    assert!(errors.is_empty(), "{errors:?}");
+    let file = parse(code);
+    let helpers = methods(&file, "LanguageLocalizer");
+
+    assert_eq!(helpers["common_greeting"], ["name"]);
+    // The attribute went with the message it belonged to
+    assert!(!helpers.contains_key("common_greeting_title"));
+    assert_eq!(helpers["common_farewell"], ["user"]);
+    // References resolve to the message that won
+    assert_eq!(helpers["common_welcome"], ["name"]);
+}
+
+#[test]
+fn reference_cycles_are_reported_with_their_path() {
+    assert_eq!(
+        error("cycle-pair", ""),
+        "Cyclic localization references: first (base.ftl:1) → second (base.ftl:2) → first"
+    );
+    // The search starts from the first key in sorted order, which puts terms first
+    assert_eq!(
+        error("cycle-triangle", ""),
+        "Cyclic localization references: -third (base.ftl:3) → first (base.ftl:1) → second (base.ftl:2) → -third"
+    );
+}
+
+#[test]
+fn diamonds_are_not_cycles() {
+    let file = generated("diamond", "");
+
+    assert_eq!(parameters(&file, "base_top"), ["depth"]);
+    assert_eq!(parameters(&file, "base_left"), ["depth"]);
+}
+
+#[test]
+fn nesting_deeper_than_the_limit_is_reported() {
+    let file = generated("nesting", "");
+    assert_eq!(parameters(&file, "base_deep"), ["depth"]);
+    assert_eq!(
+        parameters(&file, "base_selects"),
+        ["depth", "inner", "outer"]
+    );
+
+    // The select in a select puts `$depth` three levels deep, the placeables of `deep` nest four levels
+    generated("nesting", "max_nesting = 4");
+    assert_eq!(
+        error("nesting", "max_nesting = 3"),
+        "Found localizations nesting placeables more than 3 levels deep, raise max_nesting if this is intended:\n\
+         deep (base.ftl:2)"
+    );
+    assert_eq!(
+        error("nesting", "max_nesting = 2"),
+        "Found localizations nesting placeables more than 2 levels deep, raise max_nesting if this is intended:\n\
+         deep (base.ftl:2)\nselects (base.ftl:3)"
+    );
+}
+
+#[test]
+fn every_dangling_reference_is_reported_at_once() {
+    assert_eq!(
+        error("dangling", ""),
+        "Found references to localizations that don't exist:\n\
+         tooltip (base.ftl:4) references absent.label, absent does not exist\n\
+         tooltip (base.ftl:4) references attribute tooltip of login, which has no such attribute\n\
+         welcome (base.ftl:3) references login, which only has attributes\n\
+         welcome (base.ftl:3) references missing, which does not exist\n\
+         welcome (base.ftl:3) references -gone, which does not exist"
+    );
+}
+
+#[test]
+fn variables_used_as_numbers_require_numbers() {
+    let file = generated("kinds", "");
+
+    assert_eq!(
+        bounds(&file, "base_inbox"),
+        ["fluent_bundle::types::FluentNumber"]
+    );
+    assert_eq!(
+        bounds(&file, "base_price"),
+        ["fluent_bundle::types::FluentNumber"]
+    );
+    assert_eq!(
+        bounds(&file, "base_greeting"),
+        ["fluent_bundle::FluentValue<'a>"]
+    );
+    assert_eq!(
+        bounds(&file, "base_mixed"),
+        ["fluent_bundle::FluentValue<'a>"]
+    );
+    assert_eq!(
+        warnings(&file),
+        ["Variable $value of localization node mixed in base.ftl:11 is used both as a number and as text, its helper accepts any value"]
+    );
+}
+
+#[test]
+fn comment_directives_end_up_on_the_helpers() {
+    let file = generated("directives", "");
+
+    assert_eq!(
+        attributes(&file, "base_greeting"),
+        [
+            "deprecated (note = \"use welcome instead\")",
+            "allow (clippy :: too_many_arguments)"
+        ]
+    );
+    assert_eq!(attributes(&file, "base_old"), ["deprecated"]);
+    // Unknown directives are left alone
+    assert!(attributes(&file, "base_welcome").is_empty());
+
+    // The directives themselves are left out of the comment
+    let login = documentation(&file, "base_login");
+    assert_eq!(
+        login[..3],
+        [
+            "Shown on the button that starts signing in",
+            "Context: button label",
+            "Maximum length: 12"
+        ]
+    );
+    // The length holds for the attributes as well
+    assert_eq!(
+        lookup(&file, "max_length"),
+        [
+            ("login".to_string(), "Some (12usize)".to_string()),
+            ("login.tooltip".to_string(), "Some (12usize)".to_string()),
+        ]
+    );
+
+    assert_eq!(
+        error("directives-invalid", ""),
+        "The comment of login (base.ftl:2) gives it the max length \"forty\", but that is not a number"
+    );
+}
+
+#[test]
+fn no_fallback_messages_are_listed_in_order() {
+    let file = generated("no-fallback", "");
+
+    // Attributes share the directive of their message
+    assert_eq!(
+        constant(&file, "NO_FALLBACK"),
+        ["disclaimer", "imprint", "privacy", "terms", "terms.title"]
+    );
+}
+
+#[test]
+fn excluded_files_get_no_bindings() {
+    let all = generated("excluded", "");
+    let helpers = methods(&all, "LanguageLocalizer");
+    assert!(helpers.contains_key("base_welcome"));
+    assert!(helpers.contains_key("later_later"));
+    assert!(!helpers.contains_key("wip_draft") && !helpers.contains_key("_wip_draft"));
+
+    let listed = generated("excluded", r#"exclude = ["later.ftl"]"#);
+    let helpers = methods(&listed, "LanguageLocalizer");
+    assert!(helpers.contains_key("base_welcome"));
+    assert!(!helpers.contains_key("later_later"));
+
+    // Keys that only exist in excluded files don't exist as far as the bindings are concerned
+    assert_eq!(
+        error("excluded-reference", ""),
+        "Found references to localizations that don't exist:\nwelcome (base.ftl:1) references draft, which does not exist"
+    );
+}
+
+/// Compare the expansion of a case with `test-data/{case}.rs`, which is written instead with `UPDATE_SNAPSHOTS` set
+fn assert_snapshot(case: &str, options: &str) {
+    let test_data = Path::new(env!("CARGO_MANIFEST_DIR")).join("test-data");
+    // The absolute paths of the directory and the tracked files differ between checkouts
+    let expanded = prettyplease::unparse(&generated(case, options))
+        .replace(&*test_data.to_string_lossy(), "$TEST_DATA");
+
+    let snapshot = test_data.join(format!("{case}.rs"));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&snapshot, &expanded).expect("the snapshot can be written");
+    }
+    let expected = fs::read_to_string(&snapshot).expect("the snapshot exists");
+    assert!(
+        expanded == expected,
+        "The expansion changed, run the tests with UPDATE_SNAPSHOTS=1 to update {}:\n{expanded}",
+        snapshot.display()
+    );
+}
+
+#[test]
+fn expansion_matches_the_snapshot() {
+    assert_snapshot("snapshot", "");
+}
+
+#[test]
+fn term_helpers_match_the_snapshot() {
+    assert_snapshot("snapshot-terms", "terms = true");
+}
+
+/// Derive `LocalizationKeys` for a struct with the given keys, reading the directories of the cases in `test-data`
+fn derive_keys(cases: &[&str], keys: &[&str]) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let dirs: Vec<String> = cases
+        .iter()
+        .map(|case| {
+            let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("test-data")
+                .join(case);
+            format!("{:?}", dir.to_string_lossy())
+        })
+        .collect();
+    let fields: Vec<String> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| format!("#[localization_key = {key:?}] field{index}: &'static str,"))
+        .collect();
+    let input = format!(
+        "#[localization_dirs({})] struct Keys {{ {} }}",
+        dirs.join(", "),
+        fields.concat()
+    );
+    keys::derive(syn::parse_str(&input).expect("the struct parses"))
+}
+
+#[test]
+fn localization_keys_read_every_directory() {
+    // The keys of the shared directory count as well as the ones of the application overriding it
+    derive_keys(
+        &["override/shared", "override/app"],
+        &["greeting", "greeting.title", "farewell", "welcome"],
+    )
+    .unwrap();
+
+    let error = derive_keys(
+        &["override/shared", "override/app"],
+        &["greeting", "farewel"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Localization key \"farewel\" does not exist in the default localizations"
+    );
+    // Without the shared directory its keys are unknown
+    assert!(derive_keys(&["override/app"], &["greeting"]).is_ok());
+    assert!(derive_keys(&["override/app"], &["farewell"]).is_err());
+}
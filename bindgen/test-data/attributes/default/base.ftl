@@ -0,0 +1,3 @@
+login =
+    .label = Log in
+    .tooltip = Log in with { $provider }
@@ -0,0 +1 @@
+not-found = Nothing here
@@ -0,0 +1,2 @@
+not_found = Nothing found
+welcome = Welcome { $user }
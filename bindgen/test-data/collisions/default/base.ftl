@@ -0,0 +1,4 @@
+user-name = Name
+user_name = Name
+User-Name = Name
+greeting = Hello { $user-name } ({ $user_name })
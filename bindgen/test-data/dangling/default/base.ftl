@@ -0,0 +1,4 @@
+login =
+    .label = Log in
+welcome = { missing } and { -gone } after { login }
+tooltip = { login.tooltip } or { absent.label }
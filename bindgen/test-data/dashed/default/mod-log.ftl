@@ -0,0 +1,2 @@
+joined = { $user } joined
+left = Someone left
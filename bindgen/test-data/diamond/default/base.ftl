@@ -0,0 +1,4 @@
+top = { left } { right }
+left = Left { bottom }
+right = Right { bottom }
+bottom = Bottom { $depth }
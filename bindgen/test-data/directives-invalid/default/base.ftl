@@ -0,0 +1,2 @@
+# @max-length: forty
+login = Log in
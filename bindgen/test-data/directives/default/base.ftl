@@ -0,0 +1,11 @@
+# @deprecated: use welcome instead
+greeting = Hello { $user }
+# @deprecated
+old = Old
+# Shown on the button that starts signing in
+# @context: button label
+# @max-length: 12
+login = Log in
+    .tooltip = Log in with { $provider }
+# @mood: cheerful
+welcome = Welcome
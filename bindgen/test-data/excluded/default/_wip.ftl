@@ -0,0 +1 @@
+draft = Draft { $user }
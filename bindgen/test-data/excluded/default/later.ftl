@@ -0,0 +1 @@
+later = Later
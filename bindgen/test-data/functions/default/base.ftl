@@ -0,0 +1,2 @@
+price = Costs { NUMBER($amount, minimumFractionDigits: 2) }
+shout = { UPPER($name) } and { UPPER(LOWER($other)) }
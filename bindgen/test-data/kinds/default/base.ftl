@@ -0,0 +1,14 @@
+inbox = { $count ->
+    [0] No messages
+    [one] One message
+   *[other] { $count } messages
+}
+price = Costs { NUMBER($amount) }
+greeting = { $gender ->
+    [male] Welcome sir
+   *[other] Welcome
+}
+mixed = { NUMBER($value) } { $value ->
+    [male] him
+   *[other] them
+}
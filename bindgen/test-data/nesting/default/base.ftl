@@ -0,0 +1,7 @@
+shallow = { $depth }
+deep = { { { { $depth } } } }
+selects = { $outer ->
+   *[other] { $inner ->
+       *[other] { $depth }
+    }
+}
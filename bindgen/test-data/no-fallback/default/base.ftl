@@ -0,0 +1,10 @@
+# @no-fallback
+terms = Terms of service
+    .title = Terms
+# @no-fallback
+privacy = Privacy policy
+contact = Contact us
+# @no-fallback
+imprint = Imprint
+# @no-fallback
+disclaimer = Disclaimer
@@ -0,0 +1 @@
+greeting = Hi { $name }
@@ -0,0 +1,4 @@
+greeting = Hello { $user }
+    .title = Welcome back { $user }
+farewell = Goodbye { $user }
+welcome = { greeting }
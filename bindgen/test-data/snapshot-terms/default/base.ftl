@@ -0,0 +1,5 @@
+-product = Localizer
+-brand = Fluent
+-vendor = AEnterprise
+-edition = { $year } edition
+about = { -product } by { -vendor }
@@ -0,0 +1,903 @@
+const _: &str = include_str!("$TEST_DATA/snapshot/default/base.ftl");
+/// Messages with a value, sorted by key
+pub const MESSAGES: [&str; 4usize] = ["about", "greeting", "inbox", "name"];
+pub const MESSAGE_COUNT: usize = 4usize;
+/// Terms without their `-`, sorted by key
+pub const TERMS: [&str; 1usize] = ["brand"];
+pub const TERM_COUNT: usize = 1usize;
+/// Attributes of the messages as `message.attribute`
+pub const ATTRIBUTES: [&str; 2usize] = ["login.label", "login.tooltip"];
+/// Messages that only have attributes and no value, these can only be localized through their attributes
+pub const ATTRIBUTE_ONLY: [&str; 1usize] = ["login"];
+/// Messages marked with `# @no-fallback`, these never fall back to the default language
+pub const NO_FALLBACK: [&str; 0usize] = [];
+/// Messages marked with `# @no-binding`, these are validated but don't get helpers
+pub const NO_BINDING: [&str; 0usize] = [];
+/// Every message, attribute and term with the variables it needs, sorted by key
+pub const MESSAGE_META: &[fluent_localization_loader::MessageMeta] = &[
+    fluent_localization_loader::MessageMeta {
+        key: "about",
+        category: "base",
+        variables: &[],
+        is_term: false,
+    },
+    fluent_localization_loader::MessageMeta {
+        key: "brand",
+        category: "base",
+        variables: &[],
+        is_term: true,
+    },
+    fluent_localization_loader::MessageMeta {
+        key: "greeting",
+        category: "base",
+        variables: &["user"],
+        is_term: false,
+    },
+    fluent_localization_loader::MessageMeta {
+        key: "inbox",
+        category: "base",
+        variables: &["count"],
+        is_term: false,
+    },
+    fluent_localization_loader::MessageMeta {
+        key: "login.label",
+        category: "base",
+        variables: &[],
+        is_term: false,
+    },
+    fluent_localization_loader::MessageMeta {
+        key: "login.tooltip",
+        category: "base",
+        variables: &["provider"],
+        is_term: false,
+    },
+    fluent_localization_loader::MessageMeta {
+        key: "name",
+        category: "base",
+        variables: &[],
+        is_term: false,
+    },
+];
+pub struct LanguageLocalizer<'a> {
+    localizations: &'a fluent_localization_loader::LocalizationHolder,
+    language: &'a str,
+}
+/// A localizer that owns its language and shares the holder, so it can be stored or moved into a task.
+/// It implements the `Localizer` trait, with the trait in scope `owned.base_name()` works like on the localizer with texts borrowing from it.
+/// The localizer it hands out with `owned.localizer()` also has the argument helpers that aren't part of the trait
+#[derive(Clone)]
+pub struct OwnedLanguageLocalizer {
+    localizations: std::sync::Arc<fluent_localization_loader::LocalizationHolder>,
+    language: String,
+}
+impl std::fmt::Debug for LanguageLocalizer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(LanguageLocalizer))
+            .field("language", &self.language)
+            .finish_non_exhaustive()
+    }
+}
+impl std::fmt::Debug for OwnedLanguageLocalizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(stringify!(OwnedLanguageLocalizer))
+            .field("language", &self.language)
+            .finish_non_exhaustive()
+    }
+}
+impl OwnedLanguageLocalizer {
+    pub fn new(
+        holder: std::sync::Arc<fluent_localization_loader::LocalizationHolder>,
+        language: impl Into<String>,
+    ) -> OwnedLanguageLocalizer {
+        OwnedLanguageLocalizer {
+            localizations: holder,
+            language: language.into(),
+        }
+    }
+    /// Borrow a localizer for the language to call the helpers on, this is as cheap as constructing one directly
+    pub fn localizer(&self) -> LanguageLocalizer<'_> {
+        LanguageLocalizer::new(&self.localizations, &self.language)
+    }
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+    pub fn holder(
+        &self,
+    ) -> &std::sync::Arc<fluent_localization_loader::LocalizationHolder> {
+        &self.localizations
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    pub fn new(
+        holder: &'a fluent_localization_loader::LocalizationHolder,
+        language: &'a str,
+    ) -> LanguageLocalizer<'a> {
+        LanguageLocalizer {
+            localizations: holder,
+            language,
+        }
+    }
+    /// Check how much of the keys of the bindings every language directory translates, failing if any language covers
+    /// less than `min_coverage` (from 0.0 to 1.0). The summary lists the missing keys of every language
+    pub fn validate_all_bundles(
+        min_coverage: f32,
+    ) -> anyhow::Result<fluent_localization_loader::CoverageSummary> {
+        tracing::debug!("Validating the coverage of all language bundles");
+        let summary = Self::bundle_coverage(
+            &[std::path::PathBuf::from("$TEST_DATA/snapshot")],
+        )?;
+        let below: Vec<String> = summary
+            .below(min_coverage)
+            .map(|(language, coverage)| {
+                format!(
+                    "{language} ({:.1}%, missing {})", coverage.coverage * 100.0,
+                    fluent_localization_loader::fold_displayable(coverage.missing.iter(),
+                    ", ")
+                )
+            })
+            .collect();
+        if !below.is_empty() {
+            Err(
+                fluent_localization_loader::LocalizationLoadingError::new(
+                    format!(
+                        "The following languages cover less than {:.1}% of the localization keys: {}",
+                        min_coverage * 100.0,
+                        fluent_localization_loader::fold_displayable(below.iter(), "; ")
+                    ),
+                ),
+            )?
+        }
+        Ok(summary)
+    }
+    /// Coverage of the languages in the given localization directories
+    fn bundle_coverage(
+        root_dirs: &[std::path::PathBuf],
+    ) -> anyhow::Result<fluent_localization_loader::CoverageSummary> {
+        let expected: Vec<&str> = MESSAGES.into_iter().chain(ATTRIBUTES).collect();
+        Ok(
+            fluent_localization_loader::CoverageSummary::collect(
+                root_dirs,
+                &expected,
+                |file_name| { file_name.starts_with('_') || [].contains(&file_name) },
+            )?,
+        )
+    }
+    pub fn validate_default_bundle_complete() -> anyhow::Result<()> {
+        Self::validate_default_bundle_in(
+            &[std::path::PathBuf::from("$TEST_DATA/snapshot")],
+        )
+    }
+    /// `validate_default_bundle_complete` for the given localization directories
+    fn validate_default_bundle_in(
+        root_dirs: &[std::path::PathBuf],
+    ) -> anyhow::Result<()> {
+        tracing::debug!("Validating default bundle has all expected keys");
+        let default_lang = fluent_localization_loader::get_default_language()?;
+        let mut resources = Vec::new();
+        for root_dir in root_dirs {
+            let base_dir = root_dir.join(default_lang.to_string());
+            resources
+                .extend(
+                    fluent_localization_loader::load_resources_from_folder(
+                        base_dir,
+                        None,
+                    )?,
+                );
+        }
+        resources
+            .retain(|resource| {
+                !resource.file_name.starts_with('_')
+                    && ![].contains(&resource.file_name.as_str())
+            });
+        let mut found_messages: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut found_terms: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut found_attributes: std::collections::HashSet<String> = std::collections::HashSet::new();
+        let mut found_ids: std::collections::HashSet<String> = std::collections::HashSet::new();
+        resources
+            .iter()
+            .flat_map(|resource| resource.resource.entries())
+            .for_each(|entry| {
+                match entry {
+                    fluent_syntax::ast::Entry::Message(message) => {
+                        if message.value.is_some() {
+                            found_messages.insert(message.id.name.to_string());
+                        }
+                        found_ids.insert(message.id.name.to_string());
+                        for attribute in &message.attributes {
+                            found_attributes
+                                .insert(
+                                    format!("{}.{}", message.id.name, attribute.id.name),
+                                );
+                        }
+                    }
+                    fluent_syntax::ast::Entry::Term(term) => {
+                        found_terms.insert(term.id.name.to_string());
+                    }
+                    _ => {}
+                }
+            });
+        let missing_messages: Vec<&str> = MESSAGES
+            .into_iter()
+            .filter(|name| !found_messages.contains(&name.to_string()))
+            .collect();
+        let missing_terms: Vec<&str> = TERMS
+            .into_iter()
+            .filter(|name| !found_terms.contains(&name.to_string()))
+            .collect();
+        let missing_attributes: Vec<&str> = ATTRIBUTES
+            .into_iter()
+            .filter(|name| !found_attributes.contains(&name.to_string()))
+            .collect();
+        let missing_attribute_only: Vec<&str> = ATTRIBUTE_ONLY
+            .into_iter()
+            .filter(|name| !found_ids.contains(&name.to_string()))
+            .collect();
+        if !missing_messages.is_empty() || !missing_terms.is_empty()
+            || !missing_attributes.is_empty() || !missing_attribute_only.is_empty()
+        {
+            Err(
+                fluent_localization_loader::LocalizationLoadingError::new(
+                    format!(
+                        "The following localization keys where not found in the default language bundle: {}",
+                        fluent_localization_loader::fold_displayable(missing_messages
+                        .into_iter().map(| name | name.to_string()).chain(missing_terms
+                        .into_iter().map(| name | format!("-{name}")))
+                        .chain(missing_attributes.into_iter().map(| name | name
+                        .to_string())).chain(missing_attribute_only.into_iter().map(|
+                        name | format!("{name} (attributes only)"))), ", ")
+                    ),
+                ),
+            )?
+        }
+        let runtime_variables = fluent_localization_loader::message_variables(
+            &resources,
+        );
+        let list = |variables: Vec<&str>| {
+            if variables.is_empty() {
+                "no variables".to_string()
+            } else {
+                fluent_localization_loader::fold_displayable(
+                    variables.into_iter().map(|variable| format!("${variable}")),
+                    ", ",
+                )
+            }
+        };
+        let changed: Vec<String> = MESSAGE_META
+            .iter()
+            .filter(|meta| !meta.is_term)
+            .filter_map(|meta| {
+                let found = runtime_variables.get(meta.key)?;
+                if found.iter().map(String::as_str).eq(meta.variables.iter().copied()) {
+                    return None;
+                }
+                Some(
+                    format!(
+                        "{} (the bindings pass {}, the file uses {})", meta.key,
+                        list(meta.variables.to_vec()), list(found.iter()
+                        .map(String::as_str).collect())
+                    ),
+                )
+            })
+            .collect();
+        if !changed.is_empty() {
+            Err(
+                fluent_localization_loader::LocalizationLoadingError::new(
+                    format!(
+                        "The following messages in the default language bundle use other variables than the bindings were generated for: {}",
+                        fluent_localization_loader::fold_displayable(changed.iter(),
+                        "; ")
+                    ),
+                ),
+            )?
+        }
+        tracing::info!("Default bundle ({default_lang}) is valid");
+        Ok(())
+    }
+    /// Localize a message, the text is owned so it stays valid when the holder swaps in other languages.
+    /// If the language lost the message (or its value) it falls back to the text of the default language, so a translation lagging behind never panics
+    pub fn localize(
+        &self,
+        name: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> std::borrow::Cow<'a, str> {
+        self.localize_borrowed_args(name, arguments.as_ref())
+    }
+    /// Like `localize`, but also telling which language the text is in and whether that is because the language of the localizer
+    /// didn't have the message, to find missing translations from what is actually localized
+    pub fn localize_detailed(
+        &self,
+        name: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> fluent_localization_loader::LocalizeOutcome<'a> {
+        self.localize_detailed_borrowed_args(name, arguments.as_ref())
+    }
+    fn localize_borrowed_args(
+        &self,
+        name: &str,
+        arguments: Option<&fluent_bundle::FluentArgs<'a>>,
+    ) -> std::borrow::Cow<'a, str> {
+        self.localize_detailed_borrowed_args(name, arguments).text
+    }
+    fn localize_detailed_borrowed_args(
+        &self,
+        name: &str,
+        arguments: Option<&fluent_bundle::FluentArgs<'a>>,
+    ) -> fluent_localization_loader::LocalizeOutcome<'a> {
+        let no_fallback = NO_FALLBACK.contains(&name);
+        let attribute_only = ATTRIBUTE_ONLY.contains(&name);
+        let error = if attribute_only {
+            fluent_localization_loader::LocalizeError::MissingValue(name.to_string())
+        } else if no_fallback
+            && !self.localizations.translates_message(self.language, name)
+        {
+            fluent_localization_loader::LocalizeError::MissingMessage(name.to_string())
+        } else {
+            match self.localizations.try_format_message(self.language, name, arguments) {
+                Ok(message) => {
+                    return fluent_localization_loader::LocalizeOutcome::new(
+                        message,
+                        self.localizations.message_language(self.language, name),
+                        self.language,
+                    );
+                }
+                Err(error) => error,
+            }
+        };
+        let error = match error {
+            fluent_localization_loader::LocalizeError::FormatErrors { .. } => error,
+            _ if no_fallback || attribute_only => error,
+            _ => {
+                tracing::warn!(
+                    "{error} in {}, falling back to the default language", self.language
+                );
+                let default_language = self.localizations.default_language();
+                match self
+                    .localizations
+                    .try_format_message(&default_language, name, arguments)
+                {
+                    Ok(message) => {
+                        return fluent_localization_loader::LocalizeOutcome::new(
+                            message,
+                            default_language,
+                            self.language,
+                        );
+                    }
+                    Err(error) => error,
+                }
+            }
+        };
+        let message = std::borrow::Cow::Owned(
+            self.handle_errors(name, error.into_fluent_errors()),
+        );
+        fluent_localization_loader::LocalizeOutcome::new(
+            message,
+            self.language,
+            self.language,
+        )
+    }
+    /// Localize a message with or without the isolation marks fluent puts around placeables (`\u{2068}` and `\u{2069}`).
+    /// The bundles are shared so they always isolate, without isolation the marks are stripped from the result afterwards
+    pub fn localize_isolating(
+        &self,
+        name: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+        isolating: bool,
+    ) -> std::borrow::Cow<'a, str> {
+        let message = self.localize(name, arguments);
+        if isolating {
+            message
+        } else {
+            fluent_localization_loader::strip_isolation_marks(message)
+        }
+    }
+    /// Localize an attribute of a message, the same as localizing `name.attribute`
+    pub fn localize_attr(
+        &self,
+        name: &str,
+        attribute: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> std::borrow::Cow<'a, str> {
+        self.localize(&format!("{name}.{attribute}"), arguments)
+    }
+    /// Localize a message into an existing buffer, writing it as it is formatted without allocating the text first.
+    /// It falls back like `localize` does and writes the same failure message for messages that can't be found. What is written can't be
+    /// taken back, so a message that fails to format is written with fluent's placeholders for what failed (like `{$user}`) and the errors are logged
+    pub fn localize_to(
+        &self,
+        name: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+        out: &mut impl std::fmt::Write,
+    ) -> std::fmt::Result {
+        let no_fallback = NO_FALLBACK.contains(&name);
+        let attribute_only = ATTRIBUTE_ONLY.contains(&name);
+        let mut errors = Vec::new();
+        let error = if attribute_only {
+            fluent_localization_loader::LocalizeError::MissingValue(name.to_string())
+        } else if no_fallback
+            && !self.localizations.translates_message(self.language, name)
+        {
+            fluent_localization_loader::LocalizeError::MissingMessage(name.to_string())
+        } else {
+            match self
+                .localizations
+                .write_message(self.language, name, arguments.as_ref(), out, &mut errors)
+            {
+                Ok(result) => return self.log_write_errors(name, errors, result),
+                Err(error) => error,
+            }
+        };
+        let error = if no_fallback || attribute_only {
+            error
+        } else {
+            tracing::warn!(
+                "{error} in {}, falling back to the default language", self.language
+            );
+            match self
+                .localizations
+                .write_message(
+                    &self.localizations.default_language(),
+                    name,
+                    arguments.as_ref(),
+                    out,
+                    &mut errors,
+                )
+            {
+                Ok(result) => return self.log_write_errors(name, errors, result),
+                Err(error) => error,
+            }
+        };
+        out.write_str(&self.handle_errors(name, error.into_fluent_errors()))
+    }
+    fn log_write_errors(
+        &self,
+        name: &str,
+        errors: Vec<fluent_bundle::FluentError>,
+        result: std::fmt::Result,
+    ) -> std::fmt::Result {
+        if !errors.is_empty() {
+            self.handle_errors(name, errors);
+        }
+        result
+    }
+    /// Localize a message without falling back to the default text on failure, messages that can't be formatted completely are returned as an error
+    pub fn try_localize(
+        &self,
+        name: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+        self.try_localize_borrowed_args(name, arguments.as_ref())
+    }
+    pub fn try_localize_attr(
+        &self,
+        name: &str,
+        attribute: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+        self.try_localize(&format!("{name}.{attribute}"), arguments)
+    }
+    fn try_localize_borrowed_args(
+        &self,
+        name: &str,
+        arguments: Option<&fluent_bundle::FluentArgs<'a>>,
+    ) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+        if NO_FALLBACK.contains(&name)
+            && !self.localizations.translates_message(self.language, name)
+        {
+            return Err(
+                fluent_localization_loader::LocalizeError::MissingMessage(
+                    name.to_string(),
+                ),
+            );
+        }
+        self.localizations.try_format_message(self.language, name, arguments)
+    }
+    /// The value of a message that is a single element (like `{ $count }` or `{ NUMBER($amount) }`) without formatting it into a string.
+    /// Returns `None` for any other message, see `LocalizationHolder::message_value`
+    pub fn localize_value(
+        &self,
+        name: &str,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> Option<fluent_bundle::FluentValue<'static>> {
+        if NO_FALLBACK.contains(&name)
+            && !self.localizations.translates_message(self.language, name)
+        {
+            return None;
+        }
+        self.localizations.message_value(self.language, name, arguments.as_ref())
+    }
+    pub fn handle_errors(
+        &self,
+        name: &str,
+        errors: Vec<fluent_bundle::FluentError>,
+    ) -> String {
+        let errors = fluent_localization_loader::fold_displayable(
+            errors.into_iter(),
+            ", ",
+        );
+        tracing::error!("Failed to localize {name} due to following errors: {errors}");
+        format!("Failed to localize the \"{name}\" response.")
+    }
+}
+/// Every message that has bindings, for choosing the message to localize at runtime
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MessageKey {
+    BaseAbout,
+    BaseGreeting,
+    BaseInbox,
+    BaseName,
+}
+impl MessageKey {
+    pub const ALL: [MessageKey; 4usize] = [
+        MessageKey::BaseAbout,
+        MessageKey::BaseGreeting,
+        MessageKey::BaseInbox,
+        MessageKey::BaseName,
+    ];
+    /// The key of the message as it is written in the fluent files
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            MessageKey::BaseAbout => "about",
+            MessageKey::BaseGreeting => "greeting",
+            MessageKey::BaseInbox => "inbox",
+            MessageKey::BaseName => "name",
+        }
+    }
+}
+impl std::fmt::Display for MessageKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl std::str::FromStr for MessageKey {
+    type Err = fluent_localization_loader::LocalizationLoadingError;
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        match key {
+            "about" => Ok(MessageKey::BaseAbout),
+            "greeting" => Ok(MessageKey::BaseGreeting),
+            "inbox" => Ok(MessageKey::BaseInbox),
+            "name" => Ok(MessageKey::BaseName),
+            _ => {
+                Err(
+                    fluent_localization_loader::LocalizationLoadingError::new(
+                        format!("Unknown localization key {key}"),
+                    ),
+                )
+            }
+        }
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    pub fn localize_key(
+        &self,
+        key: MessageKey,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> std::borrow::Cow<'a, str> {
+        self.localize(key.as_str(), arguments)
+    }
+    pub fn try_localize_key(
+        &self,
+        key: MessageKey,
+        arguments: Option<fluent_bundle::FluentArgs<'a>>,
+    ) -> Result<std::borrow::Cow<'a, str>, fluent_localization_loader::LocalizeError> {
+        self.try_localize(key.as_str(), arguments)
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    /// The kind of value each variable of a message expects (like a number for `NUMBER($count)`), `None` if there is no such message
+    pub fn expected_variable_kinds(
+        name: &str,
+    ) -> Option<&'static [(&'static str, fluent_localization_loader::VariableKind)]> {
+        match name {
+            "about" => Some(&[]),
+            "greeting" => {
+                Some(&[("user", fluent_localization_loader::VariableKind::Unknown)])
+            }
+            "inbox" => {
+                Some(&[("count", fluent_localization_loader::VariableKind::Number)])
+            }
+            "login.label" => Some(&[]),
+            "login.tooltip" => {
+                Some(&[("provider", fluent_localization_loader::VariableKind::Unknown)])
+            }
+            "name" => Some(&[]),
+            _ => None,
+        }
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    /// The variables a message selects on with the keys of their variants (like `male`, `female` and `other` for `$gender`,
+    /// or the plural categories for a count), in the order they are used. Empty if there is no such message or it doesn't select on anything
+    pub fn select_variants(
+        name: &str,
+    ) -> &'static [(&'static str, &'static [&'static str])] {
+        match name {
+            "inbox" => &[("count", &["one", "other"])],
+            _ => &[],
+        }
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    /// The maximum length of a message from `# @max-length: 40` in its comment, which also holds for its attributes.
+    /// `None` if there is no such message or it has no maximum length
+    pub fn max_length(name: &str) -> Option<usize> {
+        match name {
+            _ => None,
+        }
+    }
+}
+/// Every language with a localization directory when the bindings were generated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Language {}
+impl Language {
+    pub const ALL: [Language; 0usize] = [];
+    /// The name of the directory of the language
+    pub fn as_str(&self) -> &'static str {
+        match *self {}
+    }
+}
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl std::str::FromStr for Language {
+    type Err = fluent_localization_loader::LocalizationLoadingError;
+    fn from_str(language: &str) -> Result<Self, Self::Err> {
+        match language {
+            _ => {
+                Err(
+                    fluent_localization_loader::LocalizationLoadingError::new(
+                        format!("Unknown language {language}"),
+                    ),
+                )
+            }
+        }
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    pub fn for_language(
+        holder: &'a fluent_localization_loader::LocalizationHolder,
+        language: Language,
+    ) -> LanguageLocalizer<'a> {
+        LanguageLocalizer::new(holder, language.as_str())
+    }
+}
+impl<'a> LanguageLocalizer<'a> {
+    /// Default text:
+    /// ```text
+    /// about = About { -brand }
+    /// ```
+    pub fn base_about(&self) -> std::borrow::Cow<'a, str> {
+        self.localize("about", None)
+    }
+    /// Default text:
+    /// ```text
+    /// login =
+    ///     .label = Log in
+    /// ```
+    pub fn base_login_label(&self) -> std::borrow::Cow<'a, str> {
+        self.localize("login.label", None)
+    }
+    /// Default text:
+    /// ```text
+    /// name = Snapshot
+    /// ```
+    pub fn base_name(&self) -> std::borrow::Cow<'a, str> {
+        self.localize("name", None)
+    }
+    /// Default text:
+    /// ```text
+    /// greeting = Hello { $user }
+    /// ```
+    ///
+    /// Variables: `$user`
+    #[allow(clippy::too_many_arguments)]
+    pub fn base_greeting<P1>(&self, user: P1) -> std::borrow::Cow<'a, str>
+    where
+        P1: Into<fluent_bundle::FluentValue<'a>>,
+    {
+        self.localize(
+            "greeting",
+            Some(
+                <fluent_bundle::FluentArgs as std::iter::FromIterator<
+                    _,
+                >>::from_iter([
+                    (
+                        "user",
+                        fluent_bundle::FluentValue::from(
+                            Into::<fluent_bundle::FluentValue<'a>>::into(user),
+                        ),
+                    ),
+                ]),
+            ),
+        )
+    }
+    /// Default text:
+    /// ```text
+    /// inbox =
+    ///     { $count ->
+    ///         [one] One message
+    ///        *[other] { $count } messages
+    ///     }
+    /// ```
+    ///
+    /// Variables: `$count`
+    #[allow(clippy::too_many_arguments)]
+    pub fn base_inbox<P1>(&self, count: P1) -> std::borrow::Cow<'a, str>
+    where
+        P1: Into<fluent_bundle::types::FluentNumber>,
+    {
+        self.localize(
+            "inbox",
+            Some(
+                <fluent_bundle::FluentArgs as std::iter::FromIterator<
+                    _,
+                >>::from_iter([
+                    (
+                        "count",
+                        fluent_bundle::FluentValue::from(
+                            Into::<fluent_bundle::types::FluentNumber>::into(count),
+                        ),
+                    ),
+                ]),
+            ),
+        )
+    }
+    /// Default text:
+    /// ```text
+    /// login =
+    ///     .tooltip = Log in with { $provider }
+    /// ```
+    ///
+    /// Variables: `$provider`
+    #[allow(clippy::too_many_arguments)]
+    pub fn base_login_tooltip<P1>(&self, provider: P1) -> std::borrow::Cow<'a, str>
+    where
+        P1: Into<fluent_bundle::FluentValue<'a>>,
+    {
+        self.localize(
+            "login.tooltip",
+            Some(
+                <fluent_bundle::FluentArgs as std::iter::FromIterator<
+                    _,
+                >>::from_iter([
+                    (
+                        "provider",
+                        fluent_bundle::FluentValue::from(
+                            Into::<fluent_bundle::FluentValue<'a>>::into(provider),
+                        ),
+                    ),
+                ]),
+            ),
+        )
+    }
+}
+/// The helpers of the localizer as a trait, so code can take any localizer (or store one as `Box<dyn Localizer>`) and tests can
+/// substitute their own, returning fixed strings for example. Helpers of the nested style are part of it under their flat name
+/// (`base_name` for `localizer.base().name()`). Texts borrow from the localizer, as implementations can't borrow for longer.
+/// It is implemented by the owned localizer, and by localizers borrowing a holder that lives as long as the program
+pub trait Localizer: Send + Sync + 'static {
+    /// Default text:
+    /// ```text
+    /// about = About { -brand }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    fn base_about<'s>(&'s self) -> std::borrow::Cow<'s, str>;
+    /// Default text:
+    /// ```text
+    /// greeting = Hello { $user }
+    /// ```
+    ///
+    /// Variables: `$user`
+    #[allow(clippy::too_many_arguments)]
+    fn base_greeting<'s>(
+        &'s self,
+        user: fluent_bundle::FluentValue<'s>,
+    ) -> std::borrow::Cow<'s, str>;
+    /// Default text:
+    /// ```text
+    /// inbox =
+    ///     { $count ->
+    ///         [one] One message
+    ///        *[other] { $count } messages
+    ///     }
+    /// ```
+    ///
+    /// Variables: `$count`
+    #[allow(clippy::too_many_arguments)]
+    fn base_inbox<'s>(
+        &'s self,
+        count: fluent_bundle::types::FluentNumber,
+    ) -> std::borrow::Cow<'s, str>;
+    /// Default text:
+    /// ```text
+    /// login =
+    ///     .label = Log in
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    fn base_login_label<'s>(&'s self) -> std::borrow::Cow<'s, str>;
+    /// Default text:
+    /// ```text
+    /// login =
+    ///     .tooltip = Log in with { $provider }
+    /// ```
+    ///
+    /// Variables: `$provider`
+    #[allow(clippy::too_many_arguments)]
+    fn base_login_tooltip<'s>(
+        &'s self,
+        provider: fluent_bundle::FluentValue<'s>,
+    ) -> std::borrow::Cow<'s, str>;
+    /// Default text:
+    /// ```text
+    /// name = Snapshot
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    fn base_name<'s>(&'s self) -> std::borrow::Cow<'s, str>;
+}
+#[allow(deprecated)]
+impl Localizer for LanguageLocalizer<'static> {
+    fn base_about<'s>(&'s self) -> std::borrow::Cow<'s, str> {
+        let localizer: &'s LanguageLocalizer<'s> = self;
+        localizer.base_about()
+    }
+    fn base_greeting<'s>(
+        &'s self,
+        user: fluent_bundle::FluentValue<'s>,
+    ) -> std::borrow::Cow<'s, str> {
+        let localizer: &'s LanguageLocalizer<'s> = self;
+        localizer.base_greeting(user)
+    }
+    fn base_inbox<'s>(
+        &'s self,
+        count: fluent_bundle::types::FluentNumber,
+    ) -> std::borrow::Cow<'s, str> {
+        let localizer: &'s LanguageLocalizer<'s> = self;
+        localizer.base_inbox(count)
+    }
+    fn base_login_label<'s>(&'s self) -> std::borrow::Cow<'s, str> {
+        let localizer: &'s LanguageLocalizer<'s> = self;
+        localizer.base_login_label()
+    }
+    fn base_login_tooltip<'s>(
+        &'s self,
+        provider: fluent_bundle::FluentValue<'s>,
+    ) -> std::borrow::Cow<'s, str> {
+        let localizer: &'s LanguageLocalizer<'s> = self;
+        localizer.base_login_tooltip(provider)
+    }
+    fn base_name<'s>(&'s self) -> std::borrow::Cow<'s, str> {
+        let localizer: &'s LanguageLocalizer<'s> = self;
+        localizer.base_name()
+    }
+}
+#[allow(deprecated)]
+impl Localizer for OwnedLanguageLocalizer {
+    fn base_about<'s>(&'s self) -> std::borrow::Cow<'s, str> {
+        self.localizer().base_about()
+    }
+    fn base_greeting<'s>(
+        &'s self,
+        user: fluent_bundle::FluentValue<'s>,
+    ) -> std::borrow::Cow<'s, str> {
+        self.localizer().base_greeting(user)
+    }
+    fn base_inbox<'s>(
+        &'s self,
+        count: fluent_bundle::types::FluentNumber,
+    ) -> std::borrow::Cow<'s, str> {
+        self.localizer().base_inbox(count)
+    }
+    fn base_login_label<'s>(&'s self) -> std::borrow::Cow<'s, str> {
+        self.localizer().base_login_label()
+    }
+    fn base_login_tooltip<'s>(
+        &'s self,
+        provider: fluent_bundle::FluentValue<'s>,
+    ) -> std::borrow::Cow<'s, str> {
+        self.localizer().base_login_tooltip(provider)
+    }
+    fn base_name<'s>(&'s self) -> std::borrow::Cow<'s, str> {
+        self.localizer().base_name()
+    }
+}
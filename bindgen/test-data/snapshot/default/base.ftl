@@ -0,0 +1,11 @@
+name = Snapshot
+greeting = Hello { $user }
+-brand = Fluent
+about = About { -brand }
+login =
+    .label = Log in
+    .tooltip = Log in with { $provider }
+inbox = { $count ->
+    [one] One message
+   *[other] { $count } messages
+}
@@ -0,0 +1,9 @@
+-brand = { $casing ->
+    [upper] FLUENT
+   *[lower] fluent
+}
+literal = Made with { -brand(casing: "upper") }
+variable = Made with { -brand($style) }
+bare = Made with { -brand }
+welcome = Welcome { $user }
+nested = { welcome }, { -brand($style) } says hi to { $other }
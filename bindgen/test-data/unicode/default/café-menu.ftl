@@ -0,0 +1 @@
+welcome = Welkom { $name }
@@ -0,0 +1,2 @@
+login-failed = { $user } failed to sign in from { $address }
+exported = { $user } exported { $count } records
@@ -0,0 +1,2 @@
+login-failed = { $user } kon niet inloggen vanaf { $address }
+exported = { $user } heeft { $count } records geëxporteerd
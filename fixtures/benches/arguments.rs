@@ -0,0 +1,89 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_localization_fixtures::{holder, LanguageLocalizer};
+
+// Ways of building the arguments of a message with five variables. `FluentArgs` is a vec sorted by key that
+// borrows `&'static str` keys, so building it never hashes or copies the keys. What differs is how many times the vec
+// grows and how many entries `set` has to shift to keep it sorted. A cached template isn't an option, `FluentArgs`
+// can't be cloned
+fn build_arguments(c: &mut Criterion) {
+    let mut group = c.benchmark_group("arguments");
+    // What the generated helpers do: collect with the keys in sorted order, so every `set` appends to a vec of the right size
+    group.bench_function("sorted_from_iter", |b| {
+        b.iter(|| {
+            black_box(FluentArgs::from_iter([
+                ("count", FluentValue::from(black_box(3))),
+                ("server", FluentValue::from(black_box("Rust"))),
+                ("source", FluentValue::from(black_box("general"))),
+                ("target", FluentValue::from(black_box("off-topic"))),
+                ("user", FluentValue::from(black_box("Ada"))),
+            ]))
+        })
+    });
+    // The keys in the order the message uses them, `set` has to move entries to keep the vec sorted
+    group.bench_function("unsorted_from_iter", |b| {
+        b.iter(|| {
+            black_box(FluentArgs::from_iter([
+                ("user", FluentValue::from(black_box("Ada"))),
+                ("count", FluentValue::from(black_box(3))),
+                ("source", FluentValue::from(black_box("general"))),
+                ("target", FluentValue::from(black_box("off-topic"))),
+                ("server", FluentValue::from(black_box("Rust"))),
+            ]))
+        })
+    });
+    // Setting the arguments one by one without reserving room, the vec grows along the way
+    group.bench_function("set_without_capacity", |b| {
+        b.iter(|| {
+            let mut arguments = FluentArgs::new();
+            arguments.set("count", black_box(3));
+            arguments.set("server", black_box("Rust"));
+            arguments.set("source", black_box("general"));
+            arguments.set("target", black_box("off-topic"));
+            arguments.set("user", black_box("Ada"));
+            black_box(arguments)
+        })
+    });
+    // Keys that aren't static have to be allocated for every call
+    group.bench_function("owned_keys", |b| {
+        b.iter(|| {
+            black_box(FluentArgs::from_iter([
+                ("count".to_string(), FluentValue::from(black_box(3))),
+                ("server".to_string(), FluentValue::from(black_box("Rust"))),
+                (
+                    "source".to_string(),
+                    FluentValue::from(black_box("general")),
+                ),
+                (
+                    "target".to_string(),
+                    FluentValue::from(black_box("off-topic")),
+                ),
+                ("user".to_string(), FluentValue::from(black_box("Ada"))),
+            ]))
+        })
+    });
+    group.finish();
+}
+
+// The generated helper in a tight loop, building the arguments is a small part of formatting the message
+fn five_argument_message(c: &mut Criterion) {
+    let holder = holder().expect("the fixtures load");
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+
+    c.bench_function("moved", |b| {
+        b.iter(|| {
+            black_box(localizer.base_moved(
+                black_box(3),
+                black_box("Rust"),
+                black_box("general"),
+                black_box("off-topic"),
+                black_box("Ada"),
+            ))
+        })
+    });
+}
+
+criterion_group!(benches, build_arguments, five_argument_message);
+criterion_main!(benches);
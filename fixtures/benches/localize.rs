@@ -0,0 +1,49 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_localization_fixtures::{holder, LanguageLocalizer};
+
+// A message with several placeables, formatted into a new string every time or written into a buffer that is reused
+fn localize_to(c: &mut Criterion) {
+    let holder = holder().expect("the fixtures load");
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+
+    let mut group = c.benchmark_group("summary");
+    group.bench_function("localize", |b| {
+        b.iter(|| black_box(localizer.base_summary(black_box("Ada"), 3, "general", "Rust")))
+    });
+    group.bench_function("localize_to", |b| {
+        let mut buffer = String::new();
+        b.iter(|| {
+            buffer.clear();
+            localizer
+                .localize_to(
+                    "summary",
+                    Some(FluentArgs::from_iter([
+                        ("channel", FluentValue::from("general")),
+                        ("count", FluentValue::from(3)),
+                        ("server", FluentValue::from("Rust")),
+                        ("user", FluentValue::from(black_box("Ada"))),
+                    ])),
+                    &mut buffer,
+                )
+                .expect("writing to a string doesn't fail");
+            black_box(buffer.len())
+        })
+    });
+    group.finish();
+}
+
+// A message without placeables is copied out of the loaded resource, as the language can be replaced while the text is in use
+fn no_arguments(c: &mut Criterion) {
+    let holder = holder().expect("the fixtures load");
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+
+    let mut group = c.benchmark_group("name");
+    group.bench_function("localized", |b| b.iter(|| black_box(localizer.base_name())));
+    group.finish();
+}
+
+criterion_group!(benches, localize_to, no_arguments);
+criterion_main!(benches);
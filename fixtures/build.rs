@@ -0,0 +1,15 @@
+use std::{env, path::PathBuf};
+
+// The macro reads the localizations relative to the directory rustc runs in, which is the workspace root.
+// Point it at the ones of this crate instead, the tests load the same directory at runtime through `env!`
+fn main() {
+    let mut path =
+        PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("cargo sets the manifest dir"));
+    path.push("localizations");
+    println!("cargo:rustc-env=TRANSLATION_DIR={}", path.display());
+    path.set_file_name("audit");
+    println!("cargo:rustc-env=AUDIT_DIR={}", path.display());
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=localizations");
+    println!("cargo:rerun-if-changed=audit");
+}
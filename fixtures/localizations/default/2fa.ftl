@@ -0,0 +1,2 @@
+# Variables named like keywords or the locals of the helpers
+command = { $self } ran { $type } with { $arguments }
@@ -0,0 +1,20 @@
+name = English
+greeting = Hello { $user }
+summary = { $user } has { $count } new messages in { $channel } on { $server }
+moved = { $user } moved { $count } messages from { $source } to { $target } on { $server }
+-brand = { $case ->
+    [possessive] Fluent's
+   *[plain] Fluent
+}
+about = About { -brand(case: "possessive") } bindings
+login =
+    .label = Sign in
+    .tooltip = Sign in with your account
+inbox = { $user } has { NUMBER($count) } { $count ->
+        [one] message
+       *[other] messages
+    } for { $gender ->
+        [male] him
+        [female] her
+       *[other] them
+    }
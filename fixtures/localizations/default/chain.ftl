@@ -0,0 +1,4 @@
+# Thirty variables gathered through a chain of messages, more than there are letters for generic parameters
+first = { $a } { $b } { $c } { $d } { $e } { $f } { $g } { $h } { $i } { $j }
+second = { first } { $k } { $l } { $m } { $n } { $o } { $p } { $q } { $r } { $s } { $t }
+all = { second } { $u } { $v } { $w } { $x } { $y } { $z } { $p1 } { $p2 } { $p3 } { $p4 }
@@ -0,0 +1,3 @@
+# @no-fallback
+disclaimer = Provided as is, without warranty of any kind
+contact = Questions go to { $address }
@@ -0,0 +1,20 @@
+name = Nederlands
+greeting = Hallo { $user }
+summary = { $user } heeft { $count } nieuwe berichten in { $channel } op { $server }
+moved = { $user } heeft { $count } berichten van { $source } naar { $target } verplaatst op { $server }
+-brand = { $case ->
+    [possessive] Fluents
+   *[plain] Fluent
+}
+about = Over { -brand(case: "possessive") } koppelingen
+login =
+    .label = Inloggen
+    .tooltip = Log in met je account
+inbox = { $user } heeft { NUMBER($count) } { $count ->
+        [one] bericht
+       *[other] berichten
+    } voor { $gender ->
+        [male] hem
+        [female] haar
+       *[other] hen
+    }
@@ -0,0 +1 @@
+contact = Vragen gaan naar { $address }
@@ -0,0 +1,91 @@
+//! Localizations exercising the generated bindings, the tests and benchmarks of the bindgen crate live here
+//! as a proc macro crate can't use its own macros.
+//!
+//! The owned localizer shares the holder and owns its language, so it can be moved into a task running on another thread:
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use fluent_localization_fixtures::{holder, Localizer, OwnedLanguageLocalizer};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let owned = OwnedLanguageLocalizer::new(Arc::new(holder().unwrap()), "nl");
+//!
+//!     let task = owned.clone();
+//!     let greeting = tokio::spawn(async move {
+//!         tokio::task::yield_now().await;
+//!         task.base_greeting("Alice".into()).into_owned()
+//!     });
+//!
+//!     assert_eq!(greeting.await.unwrap(), "Hallo \u{2068}Alice\u{2069}");
+//!     assert_eq!(owned.base_name(), "Nederlands");
+//! }
+//! ```
+
+use std::path::PathBuf;
+
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_localization_loader::{LoadError, LocalizationHolder};
+
+fluent_localization_bindgen::bind_localizations!(fallible = true, terms = true);
+
+// A localizer with another name gets a module named after it, so it can be generated next to the one above.
+// It binds a catalog of its own, the generated `localization_tests` module validates it against that directory.
+// Only this catalog generates them, the main one leaves messages untranslated in `nl` to test falling back to the default
+fluent_localization_bindgen::bind_localizations!(
+    struct = AuditLocalizer,
+    dirs = ["fixtures/audit"],
+    generate_tests = true
+);
+
+/// The same bindings generated in a module of their own, only the localizer is re-exported
+pub mod wrapped {
+    fluent_localization_bindgen::bind_localizations!(module = localizations);
+}
+
+/// Keys of a notification that is localized later on, checked against the localizations of this crate when it compiles
+#[derive(fluent_localization_bindgen::LocalizationKeys)]
+pub struct Notification {
+    #[localization_key = "greeting"]
+    pub title: &'static str,
+    #[localization_key = "login.tooltip"]
+    pub hint: &'static str,
+}
+
+/// Keys of an audit log entry, checked against the audit catalog rather than the default directory
+#[derive(fluent_localization_bindgen::LocalizationKeys)]
+#[localization_dirs("fixtures/audit")]
+pub struct AuditEntry {
+    #[localization_key = "exported"]
+    pub summary: &'static str,
+}
+
+/// Load the localizations of this crate, with `en-US` as the default language
+pub fn holder() -> Result<LocalizationHolder, LoadError> {
+    LocalizationHolder::builder()
+        .base_path(PathBuf::from(env!("TRANSLATION_DIR")))
+        .add_function("NUMBER", number)
+        .default_language("en-US".parse().expect("en-US is a valid language"))
+        .build()
+}
+
+/// Load the audit catalog of this crate, the one [`AuditLocalizer`] is generated for
+pub fn audit_holder() -> Result<LocalizationHolder, LoadError> {
+    LocalizationHolder::builder()
+        .base_path(PathBuf::from(env!("AUDIT_DIR")))
+        .default_language("en-US".parse().expect("en-US is a valid language"))
+        .build()
+}
+
+/// Fluent doesn't come with `NUMBER()`, this one applies the options to numbers and refuses anything else
+fn number<'a>(positional: &[FluentValue<'a>], named: &FluentArgs) -> FluentValue<'a> {
+    match positional {
+        [FluentValue::Number(number)] => {
+            let mut number = number.clone();
+            number.options.merge(named);
+            FluentValue::Number(number)
+        }
+        _ => FluentValue::Error,
+    }
+}
@@ -0,0 +1,371 @@
+use std::{sync::Arc, thread};
+
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_localization_fixtures::{
+    audit_holder, audit_localizer, holder, wrapped, AuditLocalizer, LanguageLocalizer, Localizer,
+    MessageKey, OwnedLanguageLocalizer, ATTRIBUTE_ONLY, MESSAGES, MESSAGE_COUNT, NO_FALLBACK,
+    TERMS, TERM_COUNT,
+};
+use fluent_localization_loader::{InMemorySource, LocalizeError, Preformatted, VariableKind};
+
+#[test]
+fn texts_outlive_the_languages_they_came_from() {
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+    let name = localizer.base_name();
+
+    let flemish = InMemorySource::builder()
+        .language("nl", [("base", "name = Vlaams")])
+        .build();
+    holder.load_language_from(&flemish, "nl").unwrap();
+
+    assert_eq!(name, "Nederlands");
+    assert_eq!(localizer.base_name(), "Vlaams");
+}
+
+#[test]
+fn messages_take_more_variables_than_there_are_letters() {
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "en-US");
+
+    let all = localizer.chain_all(
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+        26, 27, 28, 29, 30,
+    );
+
+    // The parameters are sorted by name, which puts `p1` to `p4` between `p` and `q`
+    let expected =
+        "1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 21 22 23 24 25 26 27 28 29 30 17 18 19 20";
+    assert_eq!(all.replace(['\u{2068}', '\u{2069}'], ""), expected);
+}
+
+#[test]
+fn no_fallback_messages_never_use_the_default_text() {
+    let holder = holder().unwrap();
+    let english = LanguageLocalizer::new(&holder, "en-US");
+    let dutch = LanguageLocalizer::new(&holder, "nl");
+
+    assert_eq!(NO_FALLBACK, ["disclaimer"]);
+    assert_eq!(
+        english.legal_disclaimer(),
+        "Provided as is, without warranty of any kind"
+    );
+    assert!(matches!(
+        dutch.try_legal_disclaimer(),
+        Err(LocalizeError::MissingMessage(name)) if name == "disclaimer"
+    ));
+    assert_eq!(
+        dutch.legal_disclaimer(),
+        "Failed to localize the \"disclaimer\" response."
+    );
+    let mut written = String::new();
+    dutch.localize_to("disclaimer", None, &mut written).unwrap();
+    assert!(!written.contains("warranty"));
+
+    // The other messages of the file are translated, or fall back as usual
+    let contact = dutch.legal_contact("legal@example.com");
+    assert!(contact.starts_with("Vragen gaan naar"));
+}
+
+#[test]
+fn terms_render_with_their_arguments() {
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+
+    let brand = |case: &'static str| {
+        let mut arguments = FluentArgs::new();
+        arguments.set("case", case);
+        localizer.term_brand(Some(arguments))
+    };
+    assert_eq!(brand("plain"), "Fluent");
+    assert_eq!(brand("possessive"), "Fluents");
+    // Like a message, the term needs the variables it selects on
+    assert_eq!(
+        localizer.term_brand(None),
+        "Failed to localize the \"brand\" response."
+    );
+
+    // The message passes the argument of the term itself, so its helper doesn't take any
+    assert_eq!(localizer.base_about(), "Over Fluents koppelingen");
+}
+
+#[test]
+fn attribute_only_messages_are_complete() {
+    assert_eq!(ATTRIBUTE_ONLY, ["login"]);
+    LanguageLocalizer::validate_default_bundle_complete().unwrap();
+
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+    assert_eq!(localizer.base_login_label(), "Inloggen");
+    assert_eq!(localizer.base_login_tooltip(), "Log in met je account");
+    // The message itself has no text to fall back to either
+    assert!(matches!(
+        localizer.try_localize("login", None),
+        Err(LocalizeError::MissingValue(name)) if name == "login"
+    ));
+    assert_eq!(
+        localizer.localize("login", None),
+        "Failed to localize the \"login\" response."
+    );
+}
+
+#[test]
+fn message_keys_round_trip() {
+    for key in MessageKey::ALL {
+        assert_eq!(key.to_string().parse::<MessageKey>().unwrap(), key);
+    }
+    assert!(MessageKey::ALL.contains(&MessageKey::BaseGreeting));
+    assert!("-brand".parse::<MessageKey>().is_err());
+    assert!("greeting.missing".parse::<MessageKey>().is_err());
+
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+    let key: MessageKey = "name".parse().unwrap();
+    assert_eq!(localizer.localize_key(key, None), "Nederlands");
+}
+
+#[test]
+fn key_lists_are_counted_and_sorted() {
+    // `login` only has attributes, so it isn't among the messages with a value
+    assert_eq!(
+        MESSAGES,
+        [
+            "about",
+            "all",
+            "command",
+            "contact",
+            "disclaimer",
+            "first",
+            "greeting",
+            "inbox",
+            "moved",
+            "name",
+            "second",
+            "summary",
+        ]
+    );
+    assert_eq!(MESSAGE_COUNT, 12);
+    assert_eq!(TERMS, ["brand"]);
+    assert_eq!(TERM_COUNT, 1);
+}
+
+#[test]
+fn awkward_names_still_compile() {
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "en-US");
+
+    // The file name starts with a digit, the variables are the local the arguments used to be, a keyword that can't be escaped and a keyword
+    let command = localizer._2fa_command("--all", "Ada", "ban");
+    assert_eq!(
+        command.replace(['\u{2068}', '\u{2069}'], ""),
+        "Ada ran ban with --all"
+    );
+}
+
+#[test]
+fn variables_expect_the_kind_of_value_they_are_used_as() {
+    assert_eq!(
+        LanguageLocalizer::expected_variable_kinds("inbox"),
+        Some(
+            &[
+                ("count", VariableKind::Number),
+                ("gender", VariableKind::String),
+                ("user", VariableKind::Unknown),
+            ][..]
+        )
+    );
+    assert_eq!(LanguageLocalizer::expected_variable_kinds("missing"), None);
+
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "en-US");
+    let inbox = localizer.base_inbox(1, "female", "Ada");
+    assert_eq!(
+        inbox.replace(['\u{2068}', '\u{2069}'], ""),
+        "Ada has 1 message for her"
+    );
+}
+
+#[test]
+fn selects_list_their_variants() {
+    // The plural select on `NUMBER($count)` counts as a select on `$count`
+    assert_eq!(
+        LanguageLocalizer::select_variants("inbox"),
+        &[
+            ("count", &["one", "other"][..]),
+            ("gender", &["male", "female", "other"][..]),
+        ]
+    );
+    // Terms aren't messages, even though `-brand` selects on `$case`
+    assert!(LanguageLocalizer::select_variants("-brand").is_empty());
+    assert!(LanguageLocalizer::select_variants("greeting").is_empty());
+    assert!(LanguageLocalizer::select_variants("missing").is_empty());
+}
+
+#[test]
+fn detailed_localization_reports_fallbacks() {
+    let holder = holder().unwrap();
+    let dutch = LanguageLocalizer::new(&holder, "nl");
+
+    let name = dutch.localize_detailed("name", None);
+    assert_eq!(name.text, "Nederlands");
+    assert_eq!(name.language, "nl");
+    assert!(!name.fell_back);
+
+    // The chain isn't translated to Dutch
+    let arguments: FluentArgs = ('a'..='j')
+        .map(|variable| {
+            (
+                variable.to_string(),
+                FluentValue::from(variable.to_string()),
+            )
+        })
+        .collect();
+    let first = dutch.localize_detailed("first", Some(arguments));
+    assert_eq!(
+        first.text.replace(['\u{2068}', '\u{2069}'], ""),
+        "a b c d e f g h i j"
+    );
+    assert_eq!(first.language, "en-US");
+    assert!(first.fell_back);
+
+    // Messages that never fall back stay in the language of the localizer, with the error text
+    let disclaimer = dutch.localize_detailed("disclaimer", None);
+    assert_eq!(disclaimer.language, "nl");
+    assert!(!disclaimer.fell_back);
+}
+
+#[test]
+fn pseudo_locales_only_transform_text() {
+    let holder = holder().unwrap();
+    holder
+        .with_pseudo_locale("qps-ploc", |text| text.to_uppercase())
+        .unwrap();
+    let pseudo = LanguageLocalizer::new(&holder, "qps-ploc");
+
+    // The variables, the function and the selects still work, only the text of the messages, variants and terms changed
+    let inbox = pseudo.base_inbox(1, "female", "Ada");
+    assert_eq!(
+        inbox.replace(['\u{2068}', '\u{2069}'], ""),
+        "Ada HAS 1 MESSAGE FOR HER"
+    );
+    assert_eq!(pseudo.base_about(), "ABOUT FLUENT'S BINDINGS");
+    assert_eq!(pseudo.base_login_tooltip(), "SIGN IN WITH YOUR ACCOUNT");
+}
+
+#[test]
+fn isolation_is_chosen_per_call() {
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "nl");
+    let arguments = || {
+        let mut arguments = FluentArgs::new();
+        arguments.set("user", "مريم");
+        Some(arguments)
+    };
+
+    assert_eq!(
+        localizer.localize_isolating("greeting", arguments(), true),
+        "Hallo \u{2068}مريم\u{2069}"
+    );
+    assert_eq!(
+        localizer.localize_isolating("greeting", arguments(), false),
+        "Hallo مريم"
+    );
+    // Nothing to strip from a message without placeables
+    assert_eq!(
+        localizer.localize_isolating("name", None, false),
+        "Nederlands"
+    );
+}
+
+/// Something that outlives the scope the holder was loaded in
+struct Session {
+    localizer: OwnedLanguageLocalizer,
+    fallback: Box<dyn Localizer>,
+}
+
+#[test]
+fn owned_localizers_can_be_stored() {
+    let holder = Arc::new(holder().unwrap());
+    let session = Session {
+        localizer: OwnedLanguageLocalizer::new(holder.clone(), "nl"),
+        fallback: Box::new(OwnedLanguageLocalizer::new(holder.clone(), "en-US")),
+    };
+    drop(holder);
+
+    let name = thread::spawn(move || {
+        (
+            session.localizer.localizer().base_name().into_owned(),
+            session.fallback.base_name().into_owned(),
+        )
+    })
+    .join()
+    .unwrap();
+    assert_eq!(name, ("Nederlands".to_string(), "English".to_string()));
+}
+
+#[test]
+fn preformatted_text_is_passed_as_is() {
+    let holder = holder().unwrap();
+    let localizer = LanguageLocalizer::new(&holder, "en-US");
+
+    let summary = localizer.base_summary("general", Preformatted::new("1,234.50"), "Rust", "Ada");
+    assert_eq!(
+        summary.replace(['\u{2068}', '\u{2069}'], ""),
+        "Ada has 1,234.50 new messages in general on Rust"
+    );
+
+    // Text can't be used as a number, which is why the helper of this message only takes numbers
+    let inbox = |count: FluentValue<'static>| {
+        let mut arguments = FluentArgs::new();
+        arguments.set("count", count);
+        arguments.set("gender", "other");
+        arguments.set("user", "Ada");
+        localizer
+            .try_localize("inbox", Some(arguments))
+            .map(|inbox| inbox.replace(['\u{2068}', '\u{2069}'], ""))
+    };
+    assert_eq!(inbox(1.into()).unwrap(), "Ada has 1 message for them");
+    // Without an error either: the failed `NUMBER()` is written as is and the select quietly takes the default variant
+    assert_eq!(
+        inbox(Preformatted::new("1").into()).unwrap(),
+        "Ada has NUMBER() messages for them"
+    );
+}
+
+#[test]
+fn bindings_can_be_generated_in_modules() {
+    let holder = holder().unwrap();
+
+    let wrapped = wrapped::LanguageLocalizer::new(&holder, "nl");
+    assert_eq!(wrapped.base_name(), "Nederlands");
+    assert_eq!(
+        wrapped::localizations::MESSAGES,
+        fluent_localization_fixtures::MESSAGES
+    );
+
+    // Each localizer validates the directories it was generated for
+    LanguageLocalizer::validate_default_bundle_complete().unwrap();
+}
+
+#[test]
+fn localizers_only_cover_their_own_directory() {
+    let audit_holder = audit_holder().unwrap();
+    let audit = AuditLocalizer::new(&audit_holder, "nl");
+    assert_eq!(
+        audit.audit_login_failed("10.0.0.1", "Ada"),
+        "\u{2068}Ada\u{2069} kon niet inloggen vanaf \u{2068}10.0.0.1\u{2069}"
+    );
+
+    assert_eq!(audit_localizer::MESSAGES, ["exported", "login-failed"]);
+    assert!(audit_localizer::TERMS.is_empty());
+    for key in &audit_localizer::MESSAGES {
+        assert!(
+            !MESSAGES.contains(key),
+            "{key} leaked into the main catalog"
+        );
+    }
+    assert!(!audit_localizer::MESSAGES.contains(&"greeting"));
+
+    // The main validator passes without the audit messages, the generated tests of the audit localizer run its own
+    LanguageLocalizer::validate_default_bundle_complete().unwrap();
+}
@@ -0,0 +1,94 @@
+use std::{collections::HashMap, fmt::Write, hint::black_box};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use fluent_localization_loader::{
+    InMemorySource, LoadError, LocalizationHolder, Resource, ResourceSource,
+};
+
+/// Resources that were parsed up front, so loading them only builds the bundles
+struct Parsed {
+    defaults: Vec<Resource>,
+    languages: HashMap<String, Vec<Resource>>,
+}
+
+impl ResourceSource for Parsed {
+    fn default_resources(&self) -> Result<Vec<Resource>, LoadError> {
+        Ok(self.defaults.clone())
+    }
+
+    fn languages(&self) -> Result<Vec<String>, LoadError> {
+        Ok(self.languages.keys().cloned().collect())
+    }
+
+    fn language_resources(&self, language: &str) -> Result<Vec<Resource>, LoadError> {
+        Ok(self.languages[language].clone())
+    }
+}
+
+// A large language spread over several files, like a real localization directory
+fn parsed() -> Parsed {
+    let files: Vec<(String, String)> = (0..10)
+        .map(|file| {
+            let mut content = String::new();
+            for message in 0..200 {
+                let _ = writeln!(
+                    content,
+                    "file{file}-message{message} = Message {message} of file {file} for {{ $user }}"
+                );
+            }
+            (format!("file{file}"), content)
+        })
+        .collect();
+    let files = files
+        .iter()
+        .map(|(name, content)| (name.as_str(), content.as_str()));
+    let source = InMemorySource::builder()
+        .default_files(files.clone())
+        .language("en-US", files.clone())
+        .language("nl", files)
+        .build();
+
+    let parse = |language: &str| {
+        source
+            .language_resources(language)
+            .expect("the source parses")
+    };
+    Parsed {
+        defaults: source.default_resources().expect("the source parses"),
+        languages: HashMap::from([
+            ("en-US".to_string(), parse("en-US")),
+            ("nl".to_string(), parse("nl")),
+        ]),
+    }
+}
+
+// Building the bundle of a language with and without checking it for duplicate keys
+fn strict_duplicates(c: &mut Criterion) {
+    let parsed = parsed();
+    let mut group = c.benchmark_group("load_language");
+    for strict_duplicates in [true, false] {
+        let mut holder =
+            LocalizationHolder::load_from(&parsed, &"en-US".parse().expect("en-US is valid"))
+                .expect("the resources load");
+        holder.set_strict_duplicates(strict_duplicates);
+        // The resources are the same every time, reusing the bundle would skip building it
+        holder.set_reuse_bundles(false);
+
+        let name = if strict_duplicates {
+            "strict_duplicates"
+        } else {
+            "unchecked"
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                holder
+                    .load_language_from(black_box(&parsed), "nl")
+                    .expect("there are no duplicates");
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, strict_duplicates);
+criterion_main!(benches);
@@ -0,0 +1,232 @@
+//! Memory kept alive by holders with many languages, in bytes rather than time. Every language has a bundle with a memoizer of its own,
+//! formatting fills the memoizer with the plural rules of the language
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    env, fs,
+    hint::black_box,
+    path::PathBuf,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use criterion::{
+    criterion_group, criterion_main,
+    measurement::{Measurement, ValueFormatter},
+    BenchmarkId, Criterion, Throughput,
+};
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_localization_loader::{InMemorySource, LocalizationHolder, DEFAULT_DIR};
+
+// Bytes currently allocated by the benchmark
+static LIVE: AtomicU64 = AtomicU64::new(0);
+
+struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(layout.size() as u64, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Bytes that are still allocated at the end of a measurement, the benchmarks keep what they build alive until it is taken
+struct Retained;
+
+impl Measurement for Retained {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> u64 {
+        LIVE.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: u64) -> u64 {
+        LIVE.load(Ordering::Relaxed).saturating_sub(start)
+    }
+
+    fn add(&self, a: &u64, b: &u64) -> u64 {
+        a + b
+    }
+
+    fn zero(&self) -> u64 {
+        0
+    }
+
+    fn to_f64(&self, value: &u64) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &Bytes
+    }
+}
+
+struct Bytes;
+
+impl ValueFormatter for Bytes {
+    fn scale_values(&self, typical: f64, values: &mut [f64]) -> &'static str {
+        let (divisor, unit) = match typical {
+            t if t >= 1024.0 * 1024.0 => (1024.0 * 1024.0, "MiB"),
+            t if t >= 1024.0 => (1024.0, "KiB"),
+            _ => (1.0, "B"),
+        };
+        for value in values {
+            *value /= divisor;
+        }
+        unit
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical: f64,
+        _throughput: &Throughput,
+        _values: &mut [f64],
+    ) -> &'static str {
+        "B"
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "B"
+    }
+}
+
+const BASE_LANGUAGES: [&str; 8] = ["en", "nl", "de", "fr", "pl", "ru", "ar", "cs"];
+const MESSAGES: &str = "
+items = { $count ->
+    [one] One item
+   *[other] { $count } items
+}
+greeting = Hello { $user }
+";
+
+// Regions make every language a locale of its own, with a bundle and memoizer of its own
+fn languages(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| format!("{}-{:03}", BASE_LANGUAGES[i % BASE_LANGUAGES.len()], i + 1))
+        .collect()
+}
+
+fn source(count: usize) -> InMemorySource {
+    languages(count)
+        .iter()
+        .fold(
+            InMemorySource::builder().default_files([("base", MESSAGES)]),
+            |builder, language| builder.language(language, [("base", MESSAGES)]),
+        )
+        .build()
+}
+
+// Select a plural category in every language so the memoizers create their plural rules
+fn format_all(holder: &LocalizationHolder) {
+    let arguments = FluentArgs::from_iter([("count", FluentValue::from(2))]);
+    for language in holder.languages() {
+        black_box(holder.format_message(&language, "items", Some(&arguments), &mut Vec::new()));
+    }
+}
+
+fn many_bundles(c: &mut Criterion<Retained>) {
+    let mut group = c.benchmark_group("holder");
+    for count in [1, 10, 50] {
+        let source = source(count);
+        let default_language = languages(count)[0]
+            .parse()
+            .expect("the languages are valid");
+        group.bench_with_input(BenchmarkId::new("loaded", count), &source, |b, source| {
+            b.iter_custom(|iterations| {
+                let mut retained = 0;
+                for _ in 0..iterations {
+                    let start = Retained.start();
+                    let holder = LocalizationHolder::load_from(source, &default_language)
+                        .expect("the sources load");
+                    retained += Retained.end(start);
+                    drop(black_box(holder));
+                }
+                retained
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("formatted", count),
+            &source,
+            |b, source| {
+                b.iter_custom(|iterations| {
+                    let mut retained = 0;
+                    for _ in 0..iterations {
+                        let start = Retained.start();
+                        let holder = LocalizationHolder::load_from(source, &default_language)
+                            .expect("the sources load");
+                        format_all(&holder);
+                        retained += Retained.end(start);
+                        drop(black_box(holder));
+                    }
+                    retained
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+// A localizations directory with many languages, reloading it without any changes
+fn directory(count: usize) -> PathBuf {
+    let base = env::temp_dir().join(format!("fluent-localization-memory-{}", std::process::id()));
+    for language in languages(count)
+        .iter()
+        .map(String::as_str)
+        .chain([DEFAULT_DIR])
+    {
+        fs::create_dir_all(base.join(language)).expect("the temporary directory is writable");
+        fs::write(base.join(language).join("base.ftl"), MESSAGES)
+            .expect("the temporary directory is writable");
+    }
+    base
+}
+
+// What the holder keeps after a reload, the languages it replaced are freed as soon as nothing uses them anymore
+fn reload(c: &mut Criterion<Retained>) {
+    let base_path = directory(50);
+    let mut group = c.benchmark_group("reload");
+    for reuse_bundles in [true, false] {
+        let name = if reuse_bundles {
+            "reuse_bundles"
+        } else {
+            "rebuild_bundles"
+        };
+        group.bench_function(name, |b| {
+            b.iter_custom(|iterations| {
+                let mut retained = 0;
+                for _ in 0..iterations {
+                    let holder = LocalizationHolder::builder()
+                        .base_path(base_path.clone())
+                        .default_language("en-001".parse().expect("en-001 is a valid language"))
+                        .reuse_bundles(reuse_bundles)
+                        .build()
+                        .expect("the directory loads");
+                    format_all(&holder);
+
+                    let start = Retained.start();
+                    holder.reload().expect("the directory loads");
+                    format_all(&holder);
+                    retained += Retained.end(start);
+                    drop(black_box(holder));
+                }
+                retained
+            })
+        });
+    }
+    group.finish();
+    let _ = fs::remove_dir_all(base_path);
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_measurement(Retained);
+    targets = many_bundles, reload
+}
+criterion_main!(benches);
@@ -0,0 +1,40 @@
+use std::cmp::Ordering;
+
+use icu_collator::{options::CollatorOptions, Collator, CollatorBorrowed};
+use icu_locale_core::Locale;
+use tracing::warn;
+
+use crate::LocalizationHolder;
+
+/// Create the collator for a language, returns `None` for languages ICU doesn't understand (like the pseudo language)
+pub(crate) fn collator_for(language: &str) -> Option<CollatorBorrowed<'static>> {
+    let locale = Locale::try_from_str(language).ok()?;
+    match Collator::try_new((&locale).into(), CollatorOptions::default()) {
+        Ok(collator) => Some(collator),
+        Err(e) => {
+            warn!("No collation available for {language}: {e}");
+            None
+        }
+    }
+}
+
+impl LocalizationHolder {
+    /// Compare two strings the way they are sorted in the given language.
+    /// Falls back to the default language if there is no collator for the language, and to byte order if that one is missing too
+    pub fn collate(&self, language: &str, a: &str, b: &str) -> Ordering {
+        let current = self.current();
+        match current
+            .collators
+            .get(language)
+            .or_else(|| current.collators.get(&current.default_language))
+        {
+            Some(collator) => collator.compare(a, b),
+            None => a.cmp(b),
+        }
+    }
+
+    /// Sort the items the way they are sorted in the given language, see [`LocalizationHolder::collate`]
+    pub fn sort_by_locale(&self, language: &str, items: &mut [String]) {
+        items.sort_by(|a, b| self.collate(language, a, b));
+    }
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+};
+
+use fluent_syntax::ast::Entry;
+
+use crate::{DirectorySource, LoadError, ResourceSource, DEFAULT_DIR};
+
+/// How many of the expected keys every language translates, returned by the generated `validate_all_bundles`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoverageSummary {
+    /// Coverage of every language directory, by name
+    pub languages: BTreeMap<String, LanguageCoverage>,
+}
+
+/// How many of the expected keys a single language translates
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageCoverage {
+    /// Expected keys the language doesn't have, sorted
+    pub missing: Vec<String>,
+    /// Share of the expected keys the language has, from 0.0 to 1.0
+    pub coverage: f32,
+}
+
+impl CoverageSummary {
+    /// Compare the keys of every language directory in `root_dirs` (other than `default`) with `expected`, which holds message keys
+    /// and attributes as `message.attribute`. Languages are combined across the directories, files `skip_file` returns true for are ignored
+    pub fn collect(
+        root_dirs: &[PathBuf],
+        expected: &[&str],
+        skip_file: impl Fn(&str) -> bool,
+    ) -> Result<Self, LoadError> {
+        let mut found: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+        for root_dir in root_dirs {
+            let source = DirectorySource::new(root_dir.clone());
+            for language in source.languages()? {
+                if language == DEFAULT_DIR {
+                    continue;
+                }
+
+                let keys = found.entry(language.clone()).or_default();
+                for resource in source.language_resources(&language)? {
+                    if skip_file(&resource.file_name) {
+                        continue;
+                    }
+                    for entry in resource.resource.entries() {
+                        if let Entry::Message(message) = entry {
+                            if message.value.is_some() {
+                                keys.insert(message.id.name.to_string());
+                            }
+                            for attribute in &message.attributes {
+                                keys.insert(format!("{}.{}", message.id.name, attribute.id.name));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let languages = found
+            .into_iter()
+            .map(|(language, keys)| {
+                let mut missing: Vec<String> = expected
+                    .iter()
+                    .filter(|key| !keys.contains(**key))
+                    .map(|key| key.to_string())
+                    .collect();
+                missing.sort_unstable();
+                let coverage = if expected.is_empty() {
+                    1.0
+                } else {
+                    (expected.len() - missing.len()) as f32 / expected.len() as f32
+                };
+                (language, LanguageCoverage { missing, coverage })
+            })
+            .collect();
+
+        Ok(CoverageSummary { languages })
+    }
+
+    /// Languages that cover less than `min_coverage` (from 0.0 to 1.0) of the expected keys
+    pub fn below(&self, min_coverage: f32) -> impl Iterator<Item = (&str, &LanguageCoverage)> {
+        self.languages
+            .iter()
+            .filter(move |(_, coverage)| coverage.coverage < min_coverage)
+            .map(|(language, coverage)| (language.as_str(), coverage))
+    }
+}
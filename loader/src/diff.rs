@@ -0,0 +1,92 @@
+use std::collections::{BTreeSet, HashMap};
+
+use fluent_syntax::{ast, serializer};
+
+use crate::{without_comment, LocalizationHolder};
+
+/// How a key changed between two holders
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// The text (or any of the attributes) of the entry is different
+    Changed,
+}
+
+/// A message or term (prefixed with `-`) that changed in a language
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyChange {
+    pub key: String,
+    pub kind: ChangeKind,
+}
+
+/// The keys that changed between two holders per language, see [`LocalizationHolder::diff`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LocalizationDiff {
+    /// Changes per language, sorted by key. Languages without changes are left out
+    pub changes: HashMap<String, Vec<KeyChange>>,
+}
+
+impl LocalizationDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The changes for a language, empty if nothing changed
+    pub fn language(&self, language: &str) -> &[KeyChange] {
+        self.changes.get(language).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl LocalizationHolder {
+    /// The keys that were added, removed or changed going from this holder to `other`, like after reloading to find out what to invalidate.
+    /// Compares what every language ended up with after all overrides, so a change to the default resources shows up for every language
+    /// that uses it. Comments are ignored, languages only one of the holders loaded have all their keys added or removed
+    pub fn diff(&self, other: &LocalizationHolder) -> LocalizationDiff {
+        let languages: BTreeSet<String> = self.languages().chain(other.languages()).collect();
+
+        let mut diff = LocalizationDiff::default();
+        for language in languages {
+            let before = serialized_entries(self, &language);
+            let after = serialized_entries(other, &language);
+
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            let changes: Vec<KeyChange> = keys
+                .into_iter()
+                .filter_map(|key| {
+                    let kind = match (before.get(key), after.get(key)) {
+                        (None, Some(_)) => ChangeKind::Added,
+                        (Some(_), None) => ChangeKind::Removed,
+                        (Some(before), Some(after)) if before != after => ChangeKind::Changed,
+                        _ => return None,
+                    };
+                    Some(KeyChange {
+                        key: key.clone(),
+                        kind,
+                    })
+                })
+                .collect();
+
+            if !changes.is_empty() {
+                diff.changes.insert(language, changes);
+            }
+        }
+
+        diff
+    }
+}
+
+/// The effective entries of a language written out as fluent, so they can be compared regardless of where in the files they are
+fn serialized_entries(holder: &LocalizationHolder, language: &str) -> HashMap<String, String> {
+    holder
+        .current()
+        .effective_entries(language)
+        .into_iter()
+        .map(|(key, entry)| {
+            let serialized = serializer::serialize(&ast::Resource {
+                body: vec![without_comment(entry)],
+            });
+            (key, serialized)
+        })
+        .collect()
+}
@@ -0,0 +1,127 @@
+use std::io;
+
+use include_dir::Dir;
+use tracing::{trace, warn};
+
+use crate::{
+    parse_resource, sort_by_priority, split_priority, LoadError, Normalization, Resource,
+    ResourceSource, DEFAULT_DIR, FILE_EXTENSION,
+};
+
+/// Loads resources from a directory embedded in the binary with `include_dir!`, laid out like the localizations directory:
+/// a subdirectory per language and a `default` directory
+pub struct IncludeDirSource<'a> {
+    dir: &'a Dir<'a>,
+    /// Unicode normalization applied to every file before parsing it
+    pub normalization: Normalization,
+}
+
+impl<'a> IncludeDirSource<'a> {
+    pub fn new(dir: &'a Dir<'a>) -> Self {
+        IncludeDirSource {
+            dir,
+            normalization: Normalization::None,
+        }
+    }
+
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    fn language_dir(&self, language: &str) -> Result<&'a Dir<'a>, LoadError> {
+        self.dir
+            .dirs()
+            .find(|dir| dir.path().file_name().is_some_and(|name| name == language))
+            .ok_or_else(|| {
+                LoadError::Config(format!(
+                    "The embedded localization directory {language} does not exist"
+                ))
+            })
+    }
+
+    fn load_partial(&self, language: &str) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        trace!("Loading embedded resources for {language}");
+        let mut files = Vec::new();
+        for file in self.language_dir(language)?.files() {
+            let Some(name) = file.path().file_name() else {
+                continue;
+            };
+            let name = name.to_string_lossy();
+
+            if !name.ends_with(FILE_EXTENSION) {
+                warn!("Skipping {language}/{name} because it doesn't have the proper {FILE_EXTENSION} extension");
+                continue;
+            }
+
+            let (priority, stem) = split_priority(name.strip_suffix(FILE_EXTENSION).unwrap());
+            files.push((priority, stem.to_string(), name.to_string(), file));
+        }
+
+        sort_by_priority(&mut files);
+
+        let mut loaded = Vec::new();
+        let mut errors = Vec::new();
+        for (priority, stem, name, file) in files {
+            let Some(content) = file.contents_utf8() else {
+                errors.push(LoadError::io(
+                    format!("Failed to load localization file {language}/{name}"),
+                    io::Error::new(io::ErrorKind::InvalidData, "file is not valid UTF-8"),
+                ));
+                continue;
+            };
+
+            match parse_resource(
+                &format!("{language}/{name}"),
+                priority,
+                stem,
+                name,
+                content.to_string(),
+                self.normalization,
+            ) {
+                Ok(resource) => loaded.push(resource),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        Ok((loaded, errors))
+    }
+
+    fn load(&self, language: &str) -> Result<Vec<Resource>, LoadError> {
+        let (resources, errors) = self.load_partial(language)?;
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(resources),
+        }
+    }
+}
+
+impl ResourceSource for IncludeDirSource<'_> {
+    fn default_resources(&self) -> Result<Vec<Resource>, LoadError> {
+        self.load(DEFAULT_DIR)
+    }
+
+    fn languages(&self) -> Result<Vec<String>, LoadError> {
+        Ok(self
+            .dir
+            .dirs()
+            .filter_map(|dir| dir.path().file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect())
+    }
+
+    fn language_resources(&self, language: &str) -> Result<Vec<Resource>, LoadError> {
+        self.load(language)
+    }
+
+    fn default_resources_partial(&self) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        self.load_partial(DEFAULT_DIR)
+    }
+
+    fn language_resources_partial(
+        &self,
+        language: &str,
+    ) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        self.load_partial(language)
+    }
+}
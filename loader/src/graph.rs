@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::Resource;
+
+/// Build the graph of which messages and terms reference each other, for analysis like finding unused or deeply nested messages.
+/// Nodes are the message ids, with terms prefixed by `-` like they are referenced in fluent, an edge from `a` to `b` means `a` depends on `b`.
+/// References to entries that don't exist get a node as well, so they show up as nodes without outgoing edges that no resource defines
+pub fn build_dependency_graph(resources: &[Resource]) -> DiGraph<String, ()> {
+    let mut graph = DiGraph::new();
+    let mut indices: HashMap<String, NodeIndex> = HashMap::new();
+    let mut node = |graph: &mut DiGraph<String, ()>, id: String| {
+        *indices
+            .entry(id.clone())
+            .or_insert_with(|| graph.add_node(id))
+    };
+
+    for entry in resources
+        .iter()
+        .flat_map(|resource| resource.resource.entries())
+    {
+        let (id, patterns): (String, Vec<&Pattern<&str>>) = match entry {
+            Entry::Message(message) => (
+                message.id.name.to_string(),
+                message
+                    .value
+                    .iter()
+                    .chain(message.attributes.iter().map(|attribute| &attribute.value))
+                    .collect(),
+            ),
+            Entry::Term(term) => (
+                format!("-{}", term.id.name),
+                Some(&term.value)
+                    .into_iter()
+                    .chain(term.attributes.iter().map(|attribute| &attribute.value))
+                    .collect(),
+            ),
+            _ => continue,
+        };
+
+        let from = node(&mut graph, id);
+        let mut references = Vec::new();
+        for pattern in patterns {
+            pattern_references(pattern, &mut references);
+        }
+        for reference in references {
+            let to = node(&mut graph, reference);
+            graph.update_edge(from, to, ());
+        }
+    }
+
+    graph
+}
+
+fn pattern_references(pattern: &Pattern<&str>, references: &mut Vec<String>) {
+    for element in &pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            expression_references(expression, references);
+        }
+    }
+}
+
+fn expression_references(expression: &Expression<&str>, references: &mut Vec<String>) {
+    match expression {
+        Expression::Select { selector, variants } => {
+            inline_references(selector, references);
+            for variant in variants {
+                pattern_references(&variant.value, references);
+            }
+        }
+        Expression::Inline(inline) => inline_references(inline, references),
+    }
+}
+
+fn inline_references(expression: &InlineExpression<&str>, references: &mut Vec<String>) {
+    match expression {
+        InlineExpression::MessageReference { id, .. } => references.push(id.name.to_string()),
+        InlineExpression::TermReference { id, arguments, .. } => {
+            references.push(format!("-{}", id.name));
+            for argument in arguments.iter().flat_map(|arguments| {
+                arguments
+                    .positional
+                    .iter()
+                    .chain(arguments.named.iter().map(|named| &named.value))
+            }) {
+                inline_references(argument, references);
+            }
+        }
+        InlineExpression::FunctionReference { arguments, .. } => {
+            for argument in arguments
+                .positional
+                .iter()
+                .chain(arguments.named.iter().map(|named| &named.value))
+            {
+                inline_references(argument, references);
+            }
+        }
+        InlineExpression::Placeable { expression } => expression_references(expression, references),
+        _ => (),
+    }
+}
@@ -1,39 +1,241 @@
-use std::{collections::HashMap, env, error::Error, fmt::Display, fs, path::PathBuf, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    env,
+    error::Error,
+    fmt::{Debug, Display, Write},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock},
+    time::SystemTime,
+};
 
-use fluent_bundle::{bundle::FluentBundle as RawBundle, FluentResource};
+use fluent_bundle::{
+    bundle::FluentBundle as RawBundle,
+    resolver::{errors::ReferenceKind, ResolverError},
+    FluentArgs, FluentError, FluentResource, FluentValue,
+};
 
-use anyhow::{Context, Result};
-use fluent_syntax::parser::ParserError;
+use anyhow::Result;
+use fluent_syntax::{
+    ast::{self, Entry, Expression, InlineExpression, Pattern, PatternElement},
+    parser::ParserError,
+    serializer,
+};
 use intl_memoizer::concurrent::IntlLangMemoizer;
-use tracing::{debug, error, trace, warn};
-use unic_langid::LanguageIdentifier;
+use tracing::{debug, trace, warn};
+use unic_langid::{CharacterDirection, LanguageIdentifier};
 
-type FluentBundle = RawBundle<Arc<FluentResource>, IntlLangMemoizer>;
+#[cfg(feature = "collation")]
+mod collation;
+mod coverage;
+mod diff;
+#[cfg(feature = "include_dir")]
+mod embedded;
+#[cfg(feature = "graph")]
+mod graph;
+mod memory;
+mod normalization;
+mod parse_error;
+mod pseudo;
+mod scoped;
+mod selects;
+mod source;
+#[cfg(feature = "sys-locale")]
+mod system_locale;
+mod value;
+mod variables;
+
+pub use coverage::{CoverageSummary, LanguageCoverage};
+pub use diff::{ChangeKind, KeyChange, LocalizationDiff};
+#[cfg(feature = "include_dir")]
+pub use embedded::IncludeDirSource;
+#[cfg(feature = "graph")]
+pub use graph::build_dependency_graph;
+pub use memory::{InMemorySource, InMemorySourceBuilder};
+pub use normalization::Normalization;
+#[cfg(feature = "json")]
+pub use parse_error::parse_error_to_json;
+pub use parse_error::{format_parse_error, ParseErrorDisplay};
+pub use pseudo::{pseudolocalize, PseudolocalizationSource, PSEUDO_LANGUAGE};
+pub use scoped::ScopedLocalizer;
+pub use selects::SelectDiscrepancy;
+pub use source::{DirectorySource, ResourceSource};
+#[cfg(feature = "sys-locale")]
+pub use system_locale::detect_default_language;
+pub use value::Preformatted;
+pub use variables::message_variables;
+
+/// The bundles of the holder. Every language has a single bundle with the default resources layered into it, so there is one
+/// memoizer (for plural rules and number formatting) per language and nothing of the same language to share it with.
+/// Fluent creates the memoizer of a bundle itself and only fills it with the formatters that are actually used.
+/// [`LoaderConfig::reuse_bundles`] decides whether loading a language again keeps its memoizer, [`LocalizationHolder::bundle_stats`] counts them
+pub type FluentBundle = RawBundle<Arc<FluentResource>, IntlLangMemoizer>;
 
 pub const FILE_EXTENSION: &str = ".ftl";
 pub const DEFAULT_DIR: &str = "default";
+/// Language that renders the keys of messages instead of their text when the debug locale is enabled
+pub const DEBUG_LANGUAGE: &str = "__debug__";
+/// Largest localization file that gets loaded unless configured otherwise, see [`LoaderConfig::max_file_size`]
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+const BYTE_ORDER_MARK: char = '\u{feff}';
+// Marks fluent puts around placeables to keep their text direction from affecting the text around them
+const FIRST_STRONG_ISOLATE: char = '\u{2068}';
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
 
 ///Basic wrapper to hold a resource and its original filename
 #[derive(Clone)]
 pub struct Resource {
     pub name: String,
+    /// Name of the file this resource was loaded from, including any priority prefix and the extension
+    pub file_name: String,
     pub resource: Arc<FluentResource>,
+    /// Optional load priority parsed from a numeric filename prefix (`01-base.ftl`), resources without one load first
+    pub priority: Option<u32>,
+    /// When the file was last modified, `None` for resources that weren't loaded from the filesystem or if it's unavailable.
+    /// Reloading only parses files again when this changed
+    pub modified_at: Option<SystemTime>,
+}
+
+// Only the metadata, the content of the resource has no place in logs
+impl Debug for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resource")
+            .field("name", &self.name)
+            .field("file_name", &self.file_name)
+            .field("priority", &self.priority)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `base.ftl (12 entries)`, comments count as entries as well
+impl Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({} entries)",
+            self.file_name,
+            self.resource.entries().count()
+        )
+    }
 }
 
-/// Holder to hold all the loaded bundled for localizations, as well as the currently configured default language
+/// Holder to hold all the loaded bundled for localizations, as well as the currently configured default language.
+/// Languages can be (re)loaded and the default language changed through a shared reference, so a holder shared between
+/// threads can be updated in place
 pub struct LocalizationHolder {
+    // The loaded languages, replaced as a whole when they change so nothing ever sees them half updated.
+    // Readers take their own reference, a replaced generation is freed once the last of them is done with it
+    current: RwLock<Arc<Generation>>,
+    // Held while building the next generation, so changes made at the same time don't undo each other
+    updating: Mutex<()>,
+    // Arguments that are available to every message without having to pass them explicitly.
+    // Custom values aren't `Sync`, the mutex keeps the holder shareable between threads
+    global_args: Mutex<HashMap<String, FluentValue<'static>>>,
+    debug_locale: bool,
+    // Whether messages that format to nothing show their key instead
+    show_key_on_empty: bool,
+    // Whether to test for duplicate keys within a language when loading it
+    strict_duplicates: bool,
+    // Whether languages whose files didn't change keep their bundle when loading them again
+    reuse_bundles: bool,
+    // Custom functions, kept so they can be added to bundles created after registering them
+    functions: Vec<(String, LocalizationFunction)>,
+    // The directory the holder was loaded from, if it was loaded from one
+    directory: Option<DirectorySource>,
+}
+
+/// Everything that changes when languages are loaded or the default language is changed.
+/// Bundles are shared with the generation they were copied from, only the ones of languages that changed are built again
+#[derive(Clone)]
+struct Generation {
     // Store the identifiers as strings so we don't need to convert every time we need to translate something
-    pub bundles: HashMap<String, FluentBundle>,
-    pub default_language: String,
+    bundles: HashMap<String, Arc<FluentBundle>>,
+    default_language: String,
+    // The default language as it was parsed, for callers that need the subtags
+    default_identifier: LanguageIdentifier,
+    // Resources making up each bundle in the order they were added, to look up entries the bundle doesn't expose like terms
+    layers: HashMap<String, Vec<Arc<FluentResource>>>,
+    // Messages each language defines itself, rather than getting them from the default resources
+    own_messages: HashMap<String, HashSet<String>>,
+    // Default resources the languages are layered on, kept for languages loaded later on
+    defaults: Vec<Resource>,
+    // Resources of every language as they were loaded, so reloading can reuse the ones whose file didn't change
+    resources: HashMap<String, Vec<Resource>>,
+    // Collators for every loaded language ICU knows how to sort
+    #[cfg(feature = "collation")]
+    collators: HashMap<String, Arc<icu_collator::CollatorBorrowed<'static>>>,
+}
+/// A custom function that can be called from messages, like the built in `NUMBER`
+pub type LocalizationFunction =
+    Arc<dyn for<'a> Fn(&[FluentValue<'a>], &FluentArgs) -> FluentValue<'a> + Send + Sync>;
+
+/// How many bundles a holder keeps alive, see [`LocalizationHolder::bundle_stats`].
+/// Every bundle has a memoizer of its own that caches the formatters it used, those caches are freed together with the bundle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BundleStats {
+    pub languages: usize,
+    /// Bundles of the loaded languages, languages whose files are the same share one
+    pub bundles: usize,
 }
+
+/// The direction a language is written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+    /// The language was not loaded, isn't a real language (like the pseudo language) or is written top to bottom
+    Unknown,
+}
+
+/// A key the bindings were generated for, the generated `MESSAGE_META` lists all of them for tooling
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MessageMeta {
+    /// Key of the message, `message.attribute` for attributes and without the `-` for terms
+    pub key: &'static str,
+    /// Name of the file the key is defined in, without the extension
+    pub category: &'static str,
+    /// Variables the message needs, including the ones of what it references, sorted by name
+    pub variables: &'static [&'static str],
+    pub is_term: bool,
+}
+
+/// What kind of value a message expects for a variable, based on how the default text uses it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VariableKind {
+    /// Passed to `NUMBER()` or selected on with plural categories or number keys
+    Number,
+    /// Selected on with keys that aren't numeric, like `[male]`
+    String,
+    /// Only placed in the text (where either works), or used in conflicting ways
+    Unknown,
+}
+
+/// Errors about localizations that don't match what the code expects, matched on by kind to recover from specific ones
 #[derive(Debug)]
-pub struct LocalizationLoadingError {
-    error: String,
+pub enum LocalizationLoadingError {
+    /// A localization file failed to parse, `line` and `col` start at 1
+    ParseError {
+        file: String,
+        line: usize,
+        col: usize,
+        kind: String,
+    },
+    /// A localization file that was expected to exist is missing
+    MissingFile { path: PathBuf },
+    /// Two files define the same key
+    DuplicateKey {
+        key: String,
+        file1: String,
+        file2: String,
+    },
+    /// Anything else, like keys the bindings were generated for missing from the default language
+    Other(String),
 }
 
 impl LocalizationLoadingError {
     pub fn new(error: String) -> Self {
-        LocalizationLoadingError { error }
+        LocalizationLoadingError::Other(error)
     }
 }
 
@@ -41,242 +243,2802 @@ impl Error for LocalizationLoadingError {}
 
 impl Display for LocalizationLoadingError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.error)
+        match self {
+            LocalizationLoadingError::ParseError {
+                file,
+                line,
+                col,
+                kind,
+            } => write!(f, "Failed to parse {file} at {line}:{col}: {kind}"),
+            LocalizationLoadingError::MissingFile { path } => {
+                write!(f, "Localization file {} does not exist", path.display())
+            }
+            LocalizationLoadingError::DuplicateKey { key, file1, file2 } => {
+                write!(f, "{key} is defined in both {file1} and {file2}")
+            }
+            LocalizationLoadingError::Other(error) => f.write_str(error),
+        }
     }
 }
 
-impl LocalizationHolder {
-    pub fn load() -> Result<Self> {
-        let base_path = base_path();
-        debug!(
-            "Loading localizations from {}",
-            base_path.as_path().to_string_lossy()
-        );
-        let mut bundles = HashMap::new();
-
-        let default_identifier = get_default_language()?;
-        let default = default_identifier.to_string();
+/// Errors that can occur while loading localizations, split by who needs to fix them so a CLI can map them to exit codes
+#[derive(Debug)]
+pub enum LoadError {
+    /// The environment is misconfigured, like a missing localizations directory or an invalid default language
+    Config(String),
+    /// A file or directory that has to exist is missing, like the localizations directory or the directory of a language
+    Missing { description: String, path: PathBuf },
+    /// Reading from the filesystem failed
+    Io { context: String, source: io::Error },
+    /// A localization file failed to parse, `locations` has every error with the line it is on
+    Parse {
+        file: String,
+        errors: String,
+        locations: Vec<ParseErrorDisplay>,
+    },
+    /// A localization file defines keys that were already defined by another file of the same language,
+    /// `keys` has every duplicated key with the file that defined it first
+    Duplicate {
+        file: String,
+        errors: String,
+        keys: Vec<(String, String)>,
+    },
+    /// A localization file is larger than the maximum file size, it is not read at all
+    TooLarge { file: String, size: u64, limit: u64 },
+}
 
-        let mut path = base_path.clone();
-        path.push(DEFAULT_DIR);
+impl LoadError {
+    /// Whether the localization files themselves are broken, rather than the environment they are loaded in
+    pub fn is_author_error(&self) -> bool {
+        matches!(
+            self,
+            LoadError::Parse { .. } | LoadError::Duplicate { .. } | LoadError::TooLarge { .. }
+        )
+    }
 
-        let defaults = load_resources_from_folder(path)?;
+    /// The structured errors this is made of, one for every parse error or duplicated key so they can be matched on by kind
+    pub fn variants(&self) -> Vec<LocalizationLoadingError> {
+        match self {
+            LoadError::Missing { path, .. } => {
+                vec![LocalizationLoadingError::MissingFile { path: path.clone() }]
+            }
+            LoadError::Parse {
+                file, locations, ..
+            } => locations
+                .iter()
+                .map(|location| LocalizationLoadingError::ParseError {
+                    file: file.clone(),
+                    line: location.line,
+                    col: location.column + 1,
+                    kind: location.kind.to_string(),
+                })
+                .collect(),
+            LoadError::Duplicate { file, keys, .. } => keys
+                .iter()
+                .map(|(key, first)| LocalizationLoadingError::DuplicateKey {
+                    key: key.clone(),
+                    file1: first.clone(),
+                    file2: file.clone(),
+                })
+                .collect(),
+            _ => vec![LocalizationLoadingError::Other(self.to_string())],
+        }
+    }
 
-        // Walk through the directory and start assembling our localizer
-        let base_handle =
-            fs::read_dir(base_path.clone()).context("Failed to read localizations base dir")?;
+    fn io(context: String, source: io::Error) -> Self {
+        LoadError::Io { context, source }
+    }
 
-        for result in base_handle {
-            let item_handle = result.context(
-                "Failed to get a handle when walking through the localizations directory",
-            )?;
-            //Store the file name in a var separately before the actual name is stored so it isn't dropped early
-            let underlying_name = item_handle.file_name();
-            let lang_name = underlying_name.to_string_lossy();
+    fn parse(file: String, content: &str, error_list: Vec<ParserError>) -> Self {
+        let locations: Vec<ParseErrorDisplay> = error_list
+            .iter()
+            .map(|e| format_parse_error(content, e))
+            .collect();
+        LoadError::Parse {
+            file,
+            errors: fold_displayable(locations.iter(), "\n-----\n"),
+            locations,
+        }
+    }
+}
 
-            let meta = item_handle
-                .file_type()
-                .with_context(|| format!("Failed to get item metadata for {lang_name}"))?;
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
-            if !meta.is_dir() {
-                trace!("Skipping {lang_name} because it is not a directory");
-                continue;
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Config(error) => f.write_str(error),
+            LoadError::Missing { description, path } => {
+                write!(f, "The {description} {} does not exist", path.display())
+            }
+            LoadError::Io { context, source } => write!(f, "{context}: {source}"),
+            LoadError::Parse { file, errors, .. } => {
+                write!(f, "Failed to load localization file {file}:\n{errors}")
             }
+            LoadError::Duplicate { file, errors, .. } => write!(
+                f,
+                "Failed to load localization file {file} into the duplicate test bundle:\n{errors}"
+            ),
+            LoadError::TooLarge { file, size, limit } => write!(
+                f,
+                "Localization file {file} is {size} bytes, more than the maximum of {limit} bytes"
+            ),
+        }
+    }
+}
 
-            let Ok(identifier) = lang_name.parse::<LanguageIdentifier>() else {
-                warn!("Skipping {lang_name} because it is not a valid language identifier");
-                continue;
-            };
+/// A localized message with the language its text is in, returned by the `localize_detailed` helper of the bindings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalizeOutcome<'a> {
+    pub text: Cow<'a, str>,
+    /// The language the text is in, the requested language unless it fell back
+    pub language: Cow<'a, str>,
+    /// Whether the requested language didn't have the message and the text is from another language
+    pub fell_back: bool,
+}
+
+impl<'a> LocalizeOutcome<'a> {
+    /// `requested` is the language the message was localized in, the outcome fell back if `language` is another one
+    pub fn new(text: Cow<'a, str>, language: impl Into<Cow<'a, str>>, requested: &str) -> Self {
+        let language = language.into();
+        LocalizeOutcome {
+            fell_back: language != requested,
+            text,
+            language,
+        }
+    }
+}
+
+/// Why a message could not be localized, returned by [`LocalizationHolder::try_format_message`] and the `try_` helpers of the bindings
+#[derive(Debug)]
+pub enum LocalizeError {
+    /// Neither the language nor the default language it falls back to has the message
+    MissingMessage(String),
+    /// The message exists but has no value, like a translation that only kept the attributes
+    MissingValue(String),
+    /// The message exists but doesn't have the requested attribute, holds the `message.attribute` key
+    MissingAttribute(String),
+    /// Formatting the message failed, like a missing variable or a reference to a message that doesn't exist
+    FormatErrors {
+        name: String,
+        errors: Vec<FluentError>,
+    },
+}
+
+impl LocalizeError {
+    /// The error as fluent errors, for handling it the same way as formatting errors
+    pub fn into_fluent_errors(self) -> Vec<FluentError> {
+        match self {
+            LocalizeError::MissingMessage(name) => {
+                vec![FluentError::ResolverError(ResolverError::Reference(
+                    ReferenceKind::Message {
+                        id: name,
+                        attribute: None,
+                    },
+                ))]
+            }
+            LocalizeError::MissingValue(name) => {
+                vec![FluentError::ResolverError(ResolverError::NoValue(name))]
+            }
+            LocalizeError::MissingAttribute(key) => {
+                let (name, attribute) = key.split_once('.').unwrap_or((&key, ""));
+                vec![FluentError::ResolverError(ResolverError::Reference(
+                    ReferenceKind::Message {
+                        id: name.to_string(),
+                        attribute: Some(attribute.to_string()),
+                    },
+                ))]
+            }
+            LocalizeError::FormatErrors { errors, .. } => errors,
+        }
+    }
+}
 
-            let bundle = load_bundle(base_path.clone(), identifier, defaults.clone())?;
+impl Error for LocalizeError {}
 
-            // finally add the bundle to the map
-            bundles.insert(lang_name.to_string(), bundle);
+impl Display for LocalizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalizeError::MissingMessage(name) => write!(f, "Message {name} does not exist"),
+            LocalizeError::MissingValue(name) => write!(f, "Message {name} has no value"),
+            LocalizeError::MissingAttribute(key) => write!(f, "Attribute {key} does not exist"),
+            LocalizeError::FormatErrors { name, errors } => write!(
+                f,
+                "Failed to format message {name}: {}",
+                fold_displayable(errors.iter(), ", ")
+            ),
         }
+    }
+}
 
-        Ok(LocalizationHolder {
-            bundles,
-            default_language: default,
-        })
+/// Where and how to load localizations from, `LoaderConfig::from_env` gives the configuration `LocalizationHolder::load` uses
+#[derive(Clone, Debug)]
+pub struct LoaderConfig {
+    /// Directory holding the language directories
+    pub base_path: PathBuf,
+    /// Language to fall back to when a requested language was not loaded
+    pub default_language: LanguageIdentifier,
+    /// Include the generated [`PSEUDO_LANGUAGE`] language, see [`PseudolocalizationSource`]
+    pub pseudolocale: bool,
+    /// Enable the [`DEBUG_LANGUAGE`] language, which renders message keys instead of their text
+    pub debug_locale: bool,
+    /// Show `⟦key⟧` for messages that format to an empty string, to spot accidentally empty messages during development.
+    /// Messages that are explicitly empty (`key = { "" }`) are left alone
+    pub show_key_on_empty: bool,
+    /// Unicode normalization to apply to the files before parsing them
+    pub normalization: Normalization,
+    /// Report files of the same priority defining the same key. This doubles the work of loading a language,
+    /// so it can be turned off in production when the files are already checked in CI
+    pub strict_duplicates: bool,
+    /// Keep the bundle of a language when loading it again finds its files unchanged, rather than building a new one.
+    /// The memoizer of a bundle caches a formatter for every locale and set of options it formatted with and only frees them with the bundle.
+    /// Reusing it keeps what it has built up for the next call and keeps readers that are still using the replaced languages
+    /// from holding a second one, turning this off gives every reload fresh memoizers. On by default
+    pub reuse_bundles: bool,
+    /// Only load the files whose name matches this glob pattern (like `ui-*.ftl`), all `.ftl` files are loaded without one
+    pub file_pattern: Option<glob::Pattern>,
+    /// Only load these languages (and the default language, which is always loaded), all languages are loaded without a list
+    pub languages: Option<Vec<String>>,
+    /// Files larger than this many bytes are reported instead of loaded, to not choke on contributed files that are huge by accident or on purpose
+    pub max_file_size: u64,
+}
+
+impl LoaderConfig {
+    pub fn new(base_path: PathBuf, default_language: LanguageIdentifier) -> Self {
+        LoaderConfig {
+            base_path,
+            default_language,
+            pseudolocale: false,
+            debug_locale: false,
+            show_key_on_empty: false,
+            normalization: Normalization::None,
+            strict_duplicates: true,
+            reuse_bundles: true,
+            file_pattern: None,
+            languages: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
     }
 
-    pub fn get_bundle(&self, language: &str) -> &FluentBundle {
-        self.bundles
-            .get(language)
-            .unwrap_or_else(|| self.get_bundle(&self.default_language))
+    fn directory_source(&self) -> DirectorySource {
+        let languages = self.languages.as_ref().map(|languages| {
+            let default_language = self.default_language.to_string();
+            let mut languages = languages.clone();
+            if !languages.contains(&default_language) {
+                languages.push(default_language);
+            }
+            languages
+        });
+
+        DirectorySource {
+            file_pattern: self.file_pattern.clone(),
+            languages,
+            max_file_size: self.max_file_size,
+            ..DirectorySource::new(self.base_path.clone()).with_normalization(self.normalization)
+        }
     }
 
-    pub fn get_default_bundle(&self) -> &FluentBundle {
-        self.bundles.get(&self.default_language).unwrap()
+    /// Build the configuration from the `TRANSLATION_DIR`, `DEFAULT_LANG` and `INCLUDE_LANGUAGES` environment variables
+    pub fn from_env() -> std::result::Result<Self, LoadError> {
+        LocalizationHolderBuilder::new().config()
     }
 }
 
-/// The base path localizations will be loaded from, this is controlled by the `TRANSLATION_DIR` environment variable;
-/// Will default to the `localizations` subfolder of the current working directory if not set
-pub fn base_path() -> PathBuf {
-    match env::var("TRANSLATION_DIR") {
-        Ok(location) => PathBuf::from(location),
-        Err(_) => {
-            let mut buf =
-                env::current_dir().expect("Failed to get current working directory from std::env");
-            buf.push("localizations");
-            buf
+/// Builder to configure loading in code, anything that is not set falls back to the environment variables like `LocalizationHolder::load`
+#[derive(Default)]
+pub struct LocalizationHolderBuilder {
+    base_path: Option<PathBuf>,
+    default_language: Option<LanguageIdentifier>,
+    pseudolocale: bool,
+    debug_locale: bool,
+    show_key_on_empty: bool,
+    normalization: Normalization,
+    strict_duplicates: Option<bool>,
+    reuse_bundles: Option<bool>,
+    file_pattern: Option<String>,
+    languages: Option<Vec<String>>,
+    max_file_size: Option<u64>,
+    #[cfg(feature = "sys-locale")]
+    detect_system_language: bool,
+    functions: Vec<(String, LocalizationFunction)>,
+}
+
+impl LocalizationHolderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn base_path(mut self, base_path: PathBuf) -> Self {
+        self.base_path = Some(base_path);
+        self
+    }
+
+    pub fn default_language(mut self, default_language: LanguageIdentifier) -> Self {
+        self.default_language = Some(default_language);
+        self
+    }
+
+    /// Include the generated [`PSEUDO_LANGUAGE`] language, see [`PseudolocalizationSource`]
+    pub fn add_pseudolocale(mut self) -> Self {
+        self.pseudolocale = true;
+        self
+    }
+
+    /// Enable the [`DEBUG_LANGUAGE`] language, which renders message keys instead of their text to see what is used where during development
+    pub fn debug_locale(mut self, enabled: bool) -> Self {
+        self.debug_locale = enabled;
+        self
+    }
+
+    /// Show the key of messages that format to an empty string, see [`LoaderConfig::show_key_on_empty`]
+    pub fn show_key_on_empty(mut self, enabled: bool) -> Self {
+        self.show_key_on_empty = enabled;
+        self
+    }
+
+    /// Normalize the content of every file to the given unicode normalization form before parsing it
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    /// Whether to report files of the same priority defining the same key, on by default. See [`LoaderConfig::strict_duplicates`]
+    pub fn strict_duplicates(mut self, enabled: bool) -> Self {
+        self.strict_duplicates = Some(enabled);
+        self
+    }
+
+    /// Whether languages whose files didn't change keep their bundle when they are loaded again, on by default. See [`LoaderConfig::reuse_bundles`]
+    pub fn reuse_bundles(mut self, enabled: bool) -> Self {
+        self.reuse_bundles = Some(enabled);
+        self
+    }
+
+    /// Only load the files whose name matches a glob pattern, like `ui-*.ftl` to leave out the files only the backend uses.
+    /// An invalid pattern is reported when building
+    pub fn file_pattern(mut self, pattern: &str) -> Self {
+        self.file_pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// Only load these languages, for builds that leave some of them out. The default language is always loaded
+    pub fn languages(mut self, languages: &[&str]) -> Self {
+        self.languages = Some(
+            languages
+                .iter()
+                .map(|language| language.to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Report files larger than this many bytes instead of loading them, [`DEFAULT_MAX_FILE_SIZE`] by default
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// When no default language is set in code or through `DEFAULT_LANG`, use the language of the operating system if there are localizations for it.
+    /// See [`detect_default_language`]
+    #[cfg(feature = "sys-locale")]
+    pub fn detect_system_language(mut self, enabled: bool) -> Self {
+        self.detect_system_language = enabled;
+        self
+    }
+
+    /// Make a custom function available to the messages of every language, see [`LocalizationHolder::register_function`]
+    pub fn add_function<F>(mut self, name: &str, function: F) -> Self
+    where
+        F: for<'a> Fn(&[FluentValue<'a>], &FluentArgs) -> FluentValue<'a> + Send + Sync + 'static,
+    {
+        self.functions.push((name.to_string(), Arc::new(function)));
+        self
+    }
+
+    /// Resolve the final configuration, reading the environment for anything that was not set
+    pub fn config(self) -> std::result::Result<LoaderConfig, LoadError> {
+        let base_path = self.base_path.unwrap_or_else(base_path);
+        let default_language = match self.default_language {
+            Some(default_language) => default_language,
+            #[cfg(feature = "sys-locale")]
+            None if self.detect_system_language => {
+                system_locale::detected_default_language(&base_path)?
+            }
+            None => default_language_from_env()?,
+        };
+
+        let mut config = LoaderConfig::new(base_path, default_language);
+        let (pseudolocale, debug_locale) = included_languages_from_env()?;
+        config.pseudolocale = self.pseudolocale || pseudolocale;
+        config.debug_locale = self.debug_locale || debug_locale;
+        config.show_key_on_empty = self.show_key_on_empty;
+        config.normalization = self.normalization;
+        config.strict_duplicates = self.strict_duplicates.unwrap_or(true);
+        config.reuse_bundles = self.reuse_bundles.unwrap_or(true);
+        config.file_pattern = self
+            .file_pattern
+            .as_deref()
+            .map(parse_file_pattern)
+            .transpose()?;
+        config.languages = self.languages;
+        config.max_file_size = self.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+        Ok(config)
+    }
+
+    pub fn build(mut self) -> std::result::Result<LocalizationHolder, LoadError> {
+        let functions = std::mem::take(&mut self.functions);
+        let mut holder = LocalizationHolder::load_with_config(&self.config()?)?;
+        for (name, function) in functions {
+            holder
+                .add_function(name, function)
+                .map_err(|e| LoadError::Config(format!("Failed to add function {e}")))?;
         }
+
+        Ok(holder)
     }
 }
 
-/// Get the current default language, this is controlled by the `DEFAULT_LANG` environment variable.
-/// Will default to `DEFAULT` if not set
-pub fn get_default_language() -> Result<LanguageIdentifier> {
-    let value = env::var("DEFAULT_LANG").unwrap_or("en_US".to_string());
-    value
-        .parse::<LanguageIdentifier>()
-        .with_context(|| format!("Invalid default langauge: {value}"))
+impl Debug for LocalizationHolder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalizationHolder")
+            .field("languages", &self.languages().collect::<Vec<_>>())
+            .field("default", &self.default_language())
+            .finish_non_exhaustive()
+    }
 }
 
-/// Load all fluent resource files from a directory and returns them.
-/// Only files with an .ftl extension will be loaded, does not load files from subfolders
-///
-/// Generally you don't want to be using this but rather use the load function to get an
-/// LocalizationHolder with localizations for all your languages
-///
-/// However this is public for the purposes of generating bindings through the ... crate, if if you want to do it yourself
-/// # Arguments
-/// * `path` - A PathBuf to the folder to load the resources from
-pub fn load_resources_from_folder(path: PathBuf) -> Result<Vec<Resource>> {
-    trace!("Loading resources from {path:?}");
-    let p = path.clone();
-    let path_name = p.to_string_lossy();
-    let mut loaded = Vec::new();
+impl LocalizationHolder {
+    pub fn load() -> Result<Self> {
+        Ok(Self::load_with_config(&LoaderConfig::from_env()?)?)
+    }
 
-    // Loop over all files in the directory and add them to the bundle
-    let lang_dir = fs::read_dir(path)
-        .with_context(|| format!("Failed to read localization directory {path_name}"))?;
+    pub fn builder() -> LocalizationHolderBuilder {
+        LocalizationHolderBuilder::new()
+    }
 
-    for result in lang_dir {
-        let item_handle = result.with_context(|| {
-            format!("Failed to get a file handle when walking through the {path_name} directory")
-        })?;
+    pub fn load_with_config(config: &LoaderConfig) -> std::result::Result<Self, LoadError> {
+        debug!(
+            "Loading localizations from {}",
+            config.base_path.as_path().to_string_lossy()
+        );
+        let source = config.directory_source();
 
-        let underlying_name = item_handle.file_name();
-        let name = underlying_name.to_string_lossy();
-        let meta = item_handle
-            .file_type()
-            .with_context(|| format!("Failed to get item metadata for {path_name}/{name}"))?;
+        let mut holder = Self::from_config(config);
+        if config.pseudolocale {
+            holder.load_languages(&PseudolocalizationSource::new(source))?;
+        } else {
+            holder.load_languages(&source)?;
+        }
 
-        if !meta.is_file() {
-            debug!("Skipping {path_name}/{name} because it is not a file");
-            continue;
+        Ok(holder)
+    }
+
+    /// Load all languages a source provides, layering each of them over the default resources
+    pub fn load_from(
+        source: &impl ResourceSource,
+        default_language: &LanguageIdentifier,
+    ) -> std::result::Result<Self, LoadError> {
+        let mut holder = Self::empty(default_language);
+        holder.load_languages(source)?;
+
+        Ok(holder)
+    }
+
+    /// Load localizations from a directory embedded in the binary with `include_dir!`, laid out the same as the localizations directory.
+    /// Shorthand for [`LocalizationHolder::load_from`] with an [`IncludeDirSource`]
+    #[cfg(feature = "include_dir")]
+    pub fn from_include_dir(
+        dir: &include_dir::Dir,
+        default_language: &LanguageIdentifier,
+    ) -> std::result::Result<Self, LoadError> {
+        Self::load_from(&IncludeDirSource::new(dir), default_language)
+    }
+
+    /// Like [`LocalizationHolder::load`], but rather than stopping at the first problem it tries every language and file and reports all of them.
+    /// Broken files are left out, the holder is still returned as long as the localizations directory itself could be read
+    pub fn load_collecting_errors() -> (Option<Self>, Vec<LoadError>) {
+        let config = match LoaderConfig::from_env() {
+            Ok(config) => config,
+            Err(e) => return (None, vec![e]),
+        };
+        let source = config.directory_source();
+
+        let mut holder = Self::from_config(&config);
+        let mut errors = Vec::new();
+        let result = if config.pseudolocale {
+            holder.load_languages_collecting_errors(
+                &PseudolocalizationSource::new(source),
+                &mut errors,
+            )
+        } else {
+            holder.load_languages_collecting_errors(&source, &mut errors)
+        };
+
+        match result {
+            Ok(()) => (Some(holder), errors),
+            Err(e) => {
+                errors.push(e);
+                (None, errors)
+            }
         }
+    }
 
-        if !name.ends_with(FILE_EXTENSION) {
-            warn!("Skipping {path_name}/{name} because it doesn't have the proper {FILE_EXTENSION} extension");
-            continue;
+    /// Like [`LocalizationHolder::load_from`], but reports every problem rather than stopping at the first one
+    pub fn load_from_collecting_errors(
+        source: &impl ResourceSource,
+        default_language: &LanguageIdentifier,
+    ) -> (Option<Self>, Vec<LoadError>) {
+        let mut holder = Self::empty(default_language);
+        let mut errors = Vec::new();
+
+        match holder.load_languages_collecting_errors(source, &mut errors) {
+            Ok(()) => (Some(holder), errors),
+            Err(e) => {
+                errors.push(e);
+                (None, errors)
+            }
         }
+    }
 
-        trace!("Loading localization file {path_name}/{name}");
-        let file_content = fs::read_to_string(item_handle.path())
-            .with_context(|| format!("Failed to load localization file {path_name}/{name}"))?;
+    fn load_languages(
+        &mut self,
+        source: &impl ResourceSource,
+    ) -> std::result::Result<(), LoadError> {
+        let defaults = source.default_resources()?;
 
-        let fluent_resource = FluentResource::try_new(file_content.clone())
-            .map_err(|(_, error_list)| {
-                LocalizationLoadingError::new(fold_displayable(
-                    error_list
-                        .into_iter()
-                        .map(|e| prettify_parse_error(&file_content, e)),
-                    "\n-----\n",
-                ))
-            })
-            .with_context(|| format!("Failed to load localization file {path_name}/{name}"))?;
+        self.update(|generation| {
+            generation.defaults = defaults.clone();
+            for lang_name in source.languages()? {
+                let Ok(identifier) = lang_name.parse::<LanguageIdentifier>() else {
+                    warn!("Skipping {lang_name} because it is not a valid language identifier");
+                    continue;
+                };
 
-        let arced = Arc::new(fluent_resource);
+                let resources = source.language_resources(&lang_name)?;
+                if let Some(error) = self
+                    .add_language(generation, lang_name, identifier, &defaults, resources)
+                    .into_iter()
+                    .next()
+                {
+                    return Err(error);
+                }
+            }
 
-        loaded.push(Resource {
-            name: name.strip_suffix(FILE_EXTENSION).unwrap().to_string(),
-            resource: arced,
-        })
+            Ok(())
+        })?;
+
+        Ok(())
     }
 
-    Ok(loaded)
-}
+    /// Load every language that can be loaded, pushing the problems onto `errors`.
+    /// Only fails if the source can't list its languages, leaving nothing to load
+    fn load_languages_collecting_errors(
+        &mut self,
+        source: &impl ResourceSource,
+        errors: &mut Vec<LoadError>,
+    ) -> std::result::Result<(), LoadError> {
+        let defaults = match source.default_resources_partial() {
+            Ok((defaults, default_errors)) => {
+                errors.extend(default_errors);
+                defaults
+            }
+            Err(e) => {
+                errors.push(e);
+                Vec::new()
+            }
+        };
 
-fn load_bundle(
-    mut base_path: PathBuf,
-    identifier: LanguageIdentifier,
-    defaults: Vec<Resource>,
-) -> Result<FluentBundle> {
-    let lang_name = identifier.to_string();
-    trace!("Loading language {lang_name}");
-    base_path.push(&lang_name);
+        self.update(|generation| {
+            generation.defaults = defaults.clone();
+            for lang_name in source.languages()? {
+                let Ok(identifier) = lang_name.parse::<LanguageIdentifier>() else {
+                    warn!("Skipping {lang_name} because it is not a valid language identifier");
+                    continue;
+                };
 
-    let mut bundle = FluentBundle::new_concurrent(Vec::from_iter([identifier.clone()]));
+                match source.language_resources_partial(&lang_name) {
+                    Ok((resources, resource_errors)) => {
+                        errors.extend(resource_errors);
+                        errors.extend(
+                            self.add_language(
+                                generation, lang_name, identifier, &defaults, resources,
+                            ),
+                        );
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
 
-    // to test against duplicate keys
-    let mut test_bundle = FluentBundle::new_concurrent(Vec::from_iter([identifier]));
+            Ok(())
+        })?;
 
-    for default in defaults {
-        bundle.add_resource_overriding(default.resource)
+        Ok(())
     }
 
-    for resource in load_resources_from_folder(base_path)? {
-        // First we add to the test bundle that does not have defaults, so we get errors if there are duplicate keys across the files (shouldn't happen, but ya know. me proofing)
-        test_bundle.add_resource(resource.resource.clone()).map_err(|error_list| {
-            LocalizationLoadingError::new(fold_displayable(
-        error_list
-            .into_iter()
-            // This should only ever yield overriding errors so lets keep this simple
-            .map(|e| e.to_string()),
-        "\n-----\n",
-    ))
-})
-.with_context(|| format!("Failed to load localization file {lang_name}/{} into the duplicate test bundle", resource.name))?;
+    /// Load a language that was added to the localizations directory after loading, or reload one that was changed.
+    /// Only works for holders loaded from a directory, use [`LocalizationHolder::load_language_from`] for other sources.
+    /// If loading fails nothing changes, an earlier version of the language stays loaded
+    pub fn load_language(&self, language: &str) -> std::result::Result<(), LoadError> {
+        let Some(directory) = self.directory.clone() else {
+            return Err(LoadError::Config(format!(
+                "Can't load {language}, the localizations were not loaded from a directory"
+            )));
+        };
+        let identifier = language.parse::<LanguageIdentifier>().map_err(|e| {
+            LoadError::Config(format!(
+                "Can't load {language}, it is not a valid language identifier: {e}"
+            ))
+        })?;
+        let current = self.current();
+        let cached = current
+            .resources
+            .get(language)
+            .map_or(&[][..], Vec::as_slice);
+        let resources = directory.load_cached(language, cached)?;
 
-        bundle.add_resource_overriding(resource.resource)
+        self.replace_language(language, identifier, resources)
     }
 
-    Ok(bundle)
-}
+    /// Load the default resources and every language from the localizations directory again, to pick up changes to the files.
+    /// Files that weren't modified since they were loaded are not parsed again, this goes by their modification time
+    /// so files that are changed without it changing are missed.
+    /// Only works for holders loaded from a directory, languages that were removed from the directory stay loaded.
+    /// Nothing changes if any file fails to load, the languages are only replaced once all of them loaded.
+    /// Other threads keep localizing with the languages as they were while the files load and see all of them change at once
+    pub fn reload(&self) -> std::result::Result<(), LoadError> {
+        let Some(directory) = self.directory.clone() else {
+            return Err(LoadError::Config(
+                "Can't reload, the localizations were not loaded from a directory".to_string(),
+            ));
+        };
+
+        let current = self.current();
+        let defaults = directory.load_cached(DEFAULT_DIR, &current.defaults)?;
+        let mut languages = Vec::new();
+        for lang_name in directory.languages()? {
+            let Ok(identifier) = lang_name.parse::<LanguageIdentifier>() else {
+                warn!("Skipping {lang_name} because it is not a valid language identifier");
+                continue;
+            };
 
-fn prettify_parse_error(file_content: &str, e: ParserError) -> String {
-    // figure out where our line endings are to show something at least a little more useful
-    let mut line_endings = file_content.lines().map(|line| (line.len(), line));
+            let cached = current
+                .resources
+                .get(&lang_name)
+                .map_or(&[][..], Vec::as_slice);
+            let resources = directory.load_cached(&lang_name, cached)?;
+            if self.strict_duplicates {
+                if let Some(error) = duplicate_errors(&lang_name, &identifier, &resources)
+                    .into_iter()
+                    .next()
+                {
+                    return Err(error);
+                }
+            }
+            languages.push((lang_name, identifier, resources));
+        }
+
+        // Only replace the languages once all of them loaded, a broken file leaves the holder as it was rather than half reloaded.
+        // They were checked for duplicates above already
+        self.update(|generation| {
+            generation.defaults = defaults.clone();
+            let settings = BundleSettings {
+                strict_duplicates: false,
+                ..self.bundle_settings()
+            };
+            for (lang_name, identifier, resources) in languages {
+                generation.add_language(lang_name, identifier, &defaults, resources, &settings);
+            }
 
-    let mut pos = 0;
-    let mut line = "";
-    let mut line_count = 0;
+            Ok(())
+        })
+    }
 
-    loop {
-        let Some((len, l)) = line_endings.next() else {
-            error!("Somehow fluent-rs reported a error that is past the end of the file? Was at pos {}, looking for {}", pos, e.pos.start);
-            break;
-        };
+    /// Load (or reload) a single language from a source, layered over the default resources that were loaded with the holder
+    pub fn load_language_from(
+        &self,
+        source: &impl ResourceSource,
+        language: &str,
+    ) -> std::result::Result<(), LoadError> {
+        let identifier = language.parse::<LanguageIdentifier>().map_err(|e| {
+            LoadError::Config(format!(
+                "Can't load {language}, it is not a valid language identifier: {e}"
+            ))
+        })?;
+        let resources = source.language_resources(language)?;
+
+        self.replace_language(language, identifier, resources)
+    }
+
+    /// Add a language over the default resources of the holder, replacing it if it was loaded. If there are problems with the resources
+    /// nothing changes, an earlier version of the language stays loaded
+    fn replace_language(
+        &self,
+        language: &str,
+        identifier: LanguageIdentifier,
+        resources: Vec<Resource>,
+    ) -> std::result::Result<(), LoadError> {
+        self.update(|generation| {
+            let defaults = std::mem::take(&mut generation.defaults);
+            let errors = self.add_language(
+                generation,
+                language.to_string(),
+                identifier,
+                &defaults,
+                resources,
+            );
+            generation.defaults = defaults;
+
+            errors.into_iter().next().map_or(Ok(()), Err)
+        })
+    }
+
+    /// Make another loaded language the one to fall back to, like after the configuration changed.
+    /// Fails if the language isn't loaded, as there would be nothing to fall back on
+    pub fn set_default_language(
+        &self,
+        language: &str,
+    ) -> std::result::Result<(), LocalizationLoadingError> {
+        self.update(|generation| {
+            let Some(bundle) = generation.bundles.get(language) else {
+                return Err(LocalizationLoadingError::new(format!(
+                    "Can't make {language} the default language, it is not loaded"
+                )));
+            };
+
+            if let Some(identifier) = bundle.locales.first().cloned() {
+                generation.default_identifier = identifier;
+            }
+            generation.default_language = language.to_string();
+            Ok(())
+        })
+    }
+
+    /// Unload a language, returns whether it was loaded.
+    /// The default language is needed to fall back on and can't be removed
+    pub fn remove_language(&self, language: &str) -> bool {
+        self.update(|generation| match generation.remove_language(language) {
+            true => Ok(()),
+            // Nothing to swap in
+            false => Err(()),
+        })
+        .is_ok()
+    }
 
-        //store the current line
-        line = l;
-        line_count += 1;
+    /// A holder without any languages loaded yet, with the settings from the config applied
+    fn from_config(config: &LoaderConfig) -> Self {
+        LocalizationHolder {
+            debug_locale: config.debug_locale,
+            show_key_on_empty: config.show_key_on_empty,
+            strict_duplicates: config.strict_duplicates,
+            reuse_bundles: config.reuse_bundles,
+            directory: Some(config.directory_source()),
+            ..Self::empty(&config.default_language)
+        }
+    }
 
-        // are we there yet?
-        if pos + len > e.pos.start {
-            break;
+    /// A holder without any languages loaded yet
+    fn empty(default_language: &LanguageIdentifier) -> Self {
+        LocalizationHolder {
+            current: RwLock::new(Arc::new(Generation {
+                bundles: HashMap::new(),
+                default_language: default_language.to_string(),
+                default_identifier: default_language.clone(),
+                layers: HashMap::new(),
+                own_messages: HashMap::new(),
+                defaults: Vec::new(),
+                resources: HashMap::new(),
+                #[cfg(feature = "collation")]
+                collators: HashMap::new(),
+            })),
+            updating: Mutex::new(()),
+            global_args: Mutex::new(HashMap::new()),
+            debug_locale: false,
+            show_key_on_empty: false,
+            strict_duplicates: true,
+            reuse_bundles: true,
+            functions: Vec::new(),
+            directory: None,
         }
+    }
 
-        pos += len;
+    /// The languages as they are loaded right now, they stay the same for as long as this is held even if they are replaced in the meantime
+    pub(crate) fn current(&self) -> Arc<Generation> {
+        Arc::clone(&self.current.read().unwrap_or_else(PoisonError::into_inner))
     }
 
-    let line_pos = e.pos.start - pos;
+    /// Exclusive access to the current generation, copied first if a reader still holds on to it
+    fn exclusive(&mut self) -> &mut Generation {
+        Arc::make_mut(
+            self.current
+                .get_mut()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
 
-    format!("{} at {line_count}:{line_pos}\n    {line}", e.kind)
-}
+    /// Make a change to a copy of the current generation and swap it in once it is done. Localizing keeps using the current generation
+    /// in the meantime, only the swap itself waits for it. Nothing changes if the change fails
+    fn update<T, E>(
+        &self,
+        change: impl FnOnce(&mut Generation) -> std::result::Result<T, E>,
+    ) -> std::result::Result<T, E> {
+        let _updating = self.updating.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut next = Generation::clone(&self.current());
+        let value = change(&mut next)?;
 
-#[doc(hidden)]
-pub fn fold_displayable(
-    mut iterator: impl Iterator<Item = impl Display>,
-    separator: &str,
-) -> String {
-    let Some(first) = iterator.next() else {
-        return String::new();
-    };
-    iterator.fold(first.to_string(), |assembled, new| {
-        assembled + separator + &new.to_string()
-    })
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(next);
+        Ok(value)
+    }
+
+    /// [`Generation::add_language`] with the settings of the holder
+    fn add_language(
+        &self,
+        generation: &mut Generation,
+        lang_name: String,
+        identifier: LanguageIdentifier,
+        defaults: &[Resource],
+        resources: Vec<Resource>,
+    ) -> Vec<LoadError> {
+        generation.add_language(
+            lang_name,
+            identifier,
+            defaults,
+            resources,
+            &self.bundle_settings(),
+        )
+    }
+
+    fn bundle_settings(&self) -> BundleSettings<'_> {
+        BundleSettings {
+            strict_duplicates: self.strict_duplicates,
+            reuse_bundles: self.reuse_bundles,
+            functions: &self.functions,
+        }
+    }
+
+    /// How many bundles the holder keeps alive, to keep an eye on the memory of the memoizers that come with them
+    pub fn bundle_stats(&self) -> BundleStats {
+        let current = self.current();
+        let bundles: HashSet<*const FluentBundle> =
+            current.bundles.values().map(Arc::as_ptr).collect();
+
+        BundleStats {
+            languages: current.bundles.len(),
+            bundles: bundles.len(),
+        }
+    }
+
+    /// Layer extra resources (like the translations a plugin brings along) over an already loaded language, overriding the keys they define.
+    /// A language that wasn't loaded yet is created on top of the default language.
+    /// Nothing is merged if the resources define the same key more than once, unless the duplicate check is disabled
+    pub fn merge_resources(
+        &mut self,
+        language: &str,
+        resources: Vec<Resource>,
+    ) -> std::result::Result<(), LoadError> {
+        let identifier = match self.current().bundles.get(language) {
+            Some(bundle) => bundle.locales[0].clone(),
+            None => language.parse::<LanguageIdentifier>().map_err(|e| {
+                LoadError::Config(format!(
+                    "Can't merge resources into {language}, it is not a valid language identifier: {e}"
+                ))
+            })?,
+        };
+
+        if self.strict_duplicates {
+            if let Some(error) = duplicate_errors(language, &identifier, &resources)
+                .into_iter()
+                .next()
+            {
+                return Err(error);
+            }
+        }
+
+        let functions = self.functions.clone();
+        let generation = self.exclusive();
+        if !generation.bundles.contains_key(language) {
+            debug!("Creating language {language} to merge resources into");
+            let defaults = generation
+                .layers
+                .get(&generation.default_language)
+                .cloned()
+                .unwrap_or_default();
+            // Can't fail, there are no resources to be duplicated
+            generation.add_language(
+                language.to_string(),
+                identifier,
+                &[],
+                Vec::new(),
+                &BundleSettings {
+                    strict_duplicates: false,
+                    reuse_bundles: false,
+                    functions: &functions,
+                },
+            );
+            generation.add_layers(language, defaults, &functions);
+        }
+
+        generation
+            .own_messages
+            .entry(language.to_string())
+            .or_default()
+            .extend(message_names(&resources));
+        generation.add_layers(
+            language,
+            resources
+                .into_iter()
+                .map(|resource| resource.resource)
+                .collect(),
+            &functions,
+        );
+
+        Ok(())
+    }
+
+    /// Make a custom function available to the messages of every language, including ones loaded later on.
+    /// Fails if a function with the same name was already registered
+    pub fn register_function<F>(&mut self, name: &str, function: F) -> Result<(), FluentError>
+    where
+        F: for<'a> Fn(&[FluentValue<'a>], &FluentArgs) -> FluentValue<'a> + Send + Sync + 'static,
+    {
+        self.add_function(name.to_string(), Arc::new(function))
+    }
+
+    fn add_function(
+        &mut self,
+        name: String,
+        function: LocalizationFunction,
+    ) -> Result<(), FluentError> {
+        let functions = self.functions.clone();
+        let generation = self.exclusive();
+        let languages: Vec<String> = generation.bundles.keys().cloned().collect();
+        for language in languages {
+            if let Some(bundle) = generation.exclusive_bundle(&language, &functions) {
+                add_function(bundle, &name, function.clone())?;
+            }
+        }
+        self.functions.push((name, function));
+
+        Ok(())
+    }
+
+    /// All loaded languages in alphabetical order, including generated ones like the pseudo language
+    pub fn languages(&self) -> impl Iterator<Item = String> {
+        let mut languages: Vec<String> = self.current().bundles.keys().cloned().collect();
+        languages.sort_unstable();
+        languages.into_iter()
+    }
+
+    pub fn language_count(&self) -> usize {
+        self.current().bundles.len()
+    }
+
+    /// The bundles of the loaded languages, languages whose files are the same share one
+    #[deprecated(note = "use get_bundle or the formatting methods of the holder instead")]
+    pub fn bundles(&self) -> HashMap<String, Arc<FluentBundle>> {
+        self.current().bundles.clone()
+    }
+
+    /// The bundle for a language, or the first one it falls back to that is loaded, see [`LocalizationHolder::fallback_chain`]
+    pub fn get_bundle(&self, language: &str) -> Arc<FluentBundle> {
+        Arc::clone(self.current().bundle(language))
+    }
+
+    /// The language that is fallen back on when a language isn't loaded or doesn't translate a message
+    pub fn default_language(&self) -> String {
+        self.current().default_language.clone()
+    }
+
+    /// The loaded languages that are consulted for a language, in order: the language itself, the language without its region or
+    /// script (`nl` for `nl-BE`) and finally the default language. Languages that are not loaded are left out
+    pub fn fallback_chain(&self, language: &str) -> Vec<String> {
+        self.current()
+            .fallback_chain(language)
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// The language the text of a message comes from when localizing it in a language: the language itself if it translates
+    /// the message, otherwise the first language of its fallback chain that does or the default language
+    pub fn message_language<'l>(&self, language: &'l str, name: &str) -> Cow<'l, str> {
+        if self.has_own_message(language, name) {
+            return Cow::Borrowed(language);
+        }
+
+        let current = self.current();
+        let message_language = current
+            .fallback_chain(language)
+            .into_iter()
+            .find(|candidate| current.has_own_message(candidate, name))
+            .unwrap_or(&current.default_language);
+        Cow::Owned(message_language.to_string())
+    }
+
+    /// Whether a language has a message without falling back to the default language, the language without its region (`nl` for `nl-BE`)
+    /// defining it is enough. A language that resolves to the default language itself (like `en-GB` with only `en` loaded) doesn't fall back
+    pub fn translates_message(&self, language: &str, name: &str) -> bool {
+        let message_language = self.message_language(language, name);
+        let current = self.current();
+        let default_language = current.default_language.as_str();
+        self.has_own_message(&message_language, name)
+            && (message_language != default_language
+                || current.resolved_language(language) == default_language)
+    }
+
+    pub fn get_default_bundle(&self) -> Arc<FluentBundle> {
+        let current = self.current();
+        Arc::clone(&current.bundles[&current.default_language])
+    }
+
+    /// Whether the language defines the message itself, rather than it being filled in from the default resources.
+    /// Languages that were not loaded don't have any messages of their own
+    pub fn has_own_message(&self, language: &str, name: &str) -> bool {
+        // The debug language renders every message as its key, so it never needs to fall back
+        if self.is_debug_language(language) {
+            return true;
+        }
+        self.current().has_own_message(language, name)
+    }
+
+    /// Add arguments that are available to every message (like `$app-name` or `$version`) without passing them on every call.
+    /// Arguments passed when formatting a message take precedence over these
+    pub fn inject_global_args(&mut self, args: HashMap<String, FluentValue<'static>>) {
+        self.global_args
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend(args);
+    }
+
+    /// Show `⟦key⟧` for messages that format to an empty string, see [`LoaderConfig::show_key_on_empty`]
+    pub fn set_show_key_on_empty(&mut self, enabled: bool) {
+        self.show_key_on_empty = enabled;
+    }
+
+    /// Report files of the same priority defining the same key when loading languages, see [`LoaderConfig::strict_duplicates`]
+    pub fn set_strict_duplicates(&mut self, enabled: bool) {
+        self.strict_duplicates = enabled;
+    }
+
+    /// Keep the bundles of languages whose files didn't change when loading them again, see [`LoaderConfig::reuse_bundles`]
+    pub fn set_reuse_bundles(&mut self, enabled: bool) {
+        self.reuse_bundles = enabled;
+    }
+
+    /// The default language as a `LanguageIdentifier`, for comparing subtags without parsing `default_language` again
+    pub fn default_language_identifier(&self) -> LanguageIdentifier {
+        self.current().default_identifier.clone()
+    }
+
+    /// Whether the language is the [`DEBUG_LANGUAGE`] and the debug locale is enabled
+    pub fn is_debug_language(&self, language: &str) -> bool {
+        self.debug_locale && language == DEBUG_LANGUAGE
+    }
+
+    /// The direction the language is written in, based on the script of the loaded language or its language code if it has no script.
+    /// Unlike localizing this does not fall back to the default language, that would give the wrong direction for the requested one
+    pub fn text_direction(&self, language: &str) -> TextDirection {
+        let current = self.current();
+        let Some(identifier) = current
+            .bundles
+            .get(language)
+            .and_then(|bundle| bundle.locales.first())
+        else {
+            return TextDirection::Unknown;
+        };
+
+        match identifier.character_direction() {
+            CharacterDirection::LTR => TextDirection::Ltr,
+            CharacterDirection::RTL => TextDirection::Rtl,
+            CharacterDirection::TTB => TextDirection::Unknown,
+        }
+    }
+
+    /// Whether the language is written right to left, see [`LocalizationHolder::text_direction`]
+    pub fn is_rtl(&self, language: &str) -> bool {
+        self.text_direction(language) == TextDirection::Rtl
+    }
+
+    /// Format a message in the requested language (or the default if that language was not loaded), with the global arguments merged in.
+    /// Returns `None` if the message does not exist or has no value, formatting errors are pushed onto `errors`.
+    /// For the [`DEBUG_LANGUAGE`] this returns the key of the message instead: `[key]`
+    pub fn format_message(
+        &self,
+        language: &str,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+        errors: &mut Vec<FluentError>,
+    ) -> Option<Cow<'static, str>> {
+        if self.is_debug_language(language) {
+            return Some(Cow::Owned(format!("[{name}]")));
+        }
+
+        let current = self.current();
+        let bundle = current.bundle(language);
+        let pattern = find_pattern(bundle, name).ok()?;
+
+        let formatted = self.format_pattern(bundle, pattern, arguments, errors);
+        Some(owned(self.mark_empty(name, pattern, formatted)))
+    }
+
+    /// Like [`LocalizationHolder::format_message`], but telling apart why the message could not be formatted.
+    /// Partially formatted messages are not returned, any formatting error makes this return [`LocalizeError::FormatErrors`]
+    pub fn try_format_message(
+        &self,
+        language: &str,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+    ) -> Result<Cow<'static, str>, LocalizeError> {
+        if self.is_debug_language(language) {
+            return Ok(Cow::Owned(format!("[{name}]")));
+        }
+
+        let current = self.current();
+        let bundle = current.bundle(language);
+        let pattern = find_pattern(bundle, name)?;
+
+        let mut errors = Vec::new();
+        let formatted = self.format_pattern(bundle, pattern, arguments, &mut errors);
+        if errors.is_empty() {
+            Ok(owned(self.mark_empty(name, pattern, formatted)))
+        } else {
+            Err(LocalizeError::FormatErrors {
+                name: name.to_string(),
+                errors,
+            })
+        }
+    }
+
+    /// Like [`LocalizationHolder::format_message`], but writing the message into `out` as it is formatted instead of returning it.
+    /// What is written can't be taken back, so a message that fails to format is written the way fluent formats it (with placeholders
+    /// like `{$user}` for what failed) and the errors are pushed onto `errors`. Nothing is written for messages that can't be found
+    pub fn write_message(
+        &self,
+        language: &str,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+        out: &mut impl Write,
+        errors: &mut Vec<FluentError>,
+    ) -> Result<std::fmt::Result, LocalizeError> {
+        if self.is_debug_language(language) {
+            return Ok(write!(out, "[{name}]"));
+        }
+
+        let current = self.current();
+        let bundle = current.bundle(language);
+        let pattern = find_pattern(bundle, name)?;
+
+        // Whether the message is empty is only known once it is formatted, this is a development aid so it can take the slow path
+        if self.show_key_on_empty {
+            let formatted = self.format_pattern(bundle, pattern, arguments, errors);
+            return Ok(out.write_str(&self.mark_empty(name, pattern, formatted)));
+        }
+
+        Ok(match self.merge_global_args(arguments) {
+            Some(merged) => bundle.write_pattern(out, pattern, Some(&merged), errors),
+            None => bundle.write_pattern(out, pattern, arguments, errors),
+        })
+    }
+
+    /// Format a message in every loaded language, to show them side by side when reviewing translations.
+    /// Every language gets the same arguments. Languages that don't define the message themselves get the default text
+    /// if `include_fallback` is set and are left out otherwise, languages without the message at all are always left out
+    pub fn localize_in_all(
+        &self,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+        include_fallback: bool,
+    ) -> BTreeMap<String, String> {
+        let mut localized = BTreeMap::new();
+        for language in self.languages() {
+            if !include_fallback && !self.has_own_message(&language, name) {
+                continue;
+            }
+
+            let mut errors = Vec::new();
+            if let Some(message) = self.format_message(&language, name, arguments, &mut errors) {
+                for error in errors {
+                    warn!("Error formatting {name} in {language}: {error}");
+                }
+                localized.insert(language, message.into_owned());
+            }
+        }
+
+        localized
+    }
+
+    /// Format a term in the requested language (or the language it falls back to if it was not loaded), mainly for previewing them in tooling.
+    /// Returns `None` if the term does not exist, formatting errors are pushed onto `errors`
+    pub fn format_term(
+        &self,
+        language: &str,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+        errors: &mut Vec<FluentError>,
+    ) -> Option<Cow<'static, str>> {
+        let current = self.current();
+        let language = current.resolved_language(language);
+
+        // Later layers override earlier ones, so search from the back
+        let pattern = current
+            .layers
+            .get(language)?
+            .iter()
+            .rev()
+            .find_map(|resource| {
+                resource.entries().find_map(|entry| match entry {
+                    Entry::Term(term) if term.id.name == name => Some(&term.value),
+                    _ => None,
+                })
+            })?;
+
+        Some(owned(self.format_pattern(
+            current.bundle(language),
+            pattern,
+            arguments,
+            errors,
+        )))
+    }
+
+    /// Serialize what a language (or the language it falls back to if it was not loaded) actually ended up with after all overrides,
+    /// to diff against the source files when debugging which file wins.
+    /// Every message and term is written once with the text that is used, sorted by key and without comments
+    pub fn dump(&self, language: &str) -> String {
+        let current = self.current();
+        let mut entries: Vec<(String, &Entry<&str>)> = current
+            .effective_entries(current.resolved_language(language))
+            .into_iter()
+            .collect();
+        entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        let body = entries
+            .into_iter()
+            .map(|(_, entry)| without_comment(entry))
+            .collect();
+
+        serializer::serialize(&ast::Resource { body })
+    }
+
+    fn global_args(&self) -> MutexGuard<'_, HashMap<String, FluentValue<'static>>> {
+        // Nothing can panic while holding the lock, but there is no reason to give up on the arguments if something did
+        self.global_args
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replace an empty formatted message with `⟦name⟧` if enabled, unless the message is explicitly empty (`{ "" }`)
+    fn mark_empty<'s>(
+        &self,
+        name: &str,
+        pattern: &Pattern<&str>,
+        formatted: Cow<'s, str>,
+    ) -> Cow<'s, str> {
+        if !self.show_key_on_empty || !formatted.is_empty() {
+            return formatted;
+        }
+
+        let deliberate = matches!(
+            pattern.elements.as_slice(),
+            [PatternElement::Placeable {
+                expression: Expression::Inline(InlineExpression::StringLiteral { value: "" })
+            }]
+        );
+        if deliberate {
+            formatted
+        } else {
+            Cow::Owned(format!("⟦{name}⟧"))
+        }
+    }
+
+    fn format_pattern<'s>(
+        &'s self,
+        bundle: &'s FluentBundle,
+        pattern: &'s Pattern<&'s str>,
+        arguments: Option<&FluentArgs>,
+        errors: &mut Vec<FluentError>,
+    ) -> Cow<'s, str> {
+        match self.merge_global_args(arguments) {
+            Some(merged) => bundle.format_pattern(pattern, Some(&merged), errors),
+            None => bundle.format_pattern(pattern, arguments, errors),
+        }
+    }
+
+    /// The arguments with the global ones merged in, `None` when there are no global arguments and the arguments can be used as is
+    fn merge_global_args<'v>(
+        &self,
+        arguments: Option<&'v FluentArgs<'v>>,
+    ) -> Option<FluentArgs<'v>> {
+        let global_args = self.global_args();
+        if global_args.is_empty() {
+            return None;
+        }
+
+        let mut merged = FluentArgs::with_capacity(
+            global_args.len() + arguments.map_or(0, |arguments| arguments.iter().count()),
+        );
+        for (key, value) in global_args.iter() {
+            merged.set(key.clone(), value.clone());
+        }
+        drop(global_args);
+        for (key, value) in arguments.into_iter().flat_map(FluentArgs::iter) {
+            merged.set(key, value.clone());
+        }
+
+        Some(merged)
+    }
+}
+
+/// The base path localizations will be loaded from, this is controlled by the `TRANSLATION_DIR` environment variable;
+/// Will default to the `localizations` subfolder of the current working directory if not set
+pub fn base_path() -> PathBuf {
+    match env::var("TRANSLATION_DIR") {
+        Ok(location) => PathBuf::from(location),
+        Err(_) => {
+            let mut buf =
+                env::current_dir().expect("Failed to get current working directory from std::env");
+            buf.push("localizations");
+            buf
+        }
+    }
+}
+
+/// Get the current default language, this is controlled by the `DEFAULT_LANG` environment variable.
+/// Will default to `DEFAULT` if not set
+pub fn get_default_language() -> Result<LanguageIdentifier> {
+    Ok(default_language_from_env()?)
+}
+
+/// Which generated languages were requested through the comma separated `INCLUDE_LANGUAGES` environment variable,
+/// as (pseudolocale, debug locale)
+fn included_languages_from_env() -> std::result::Result<(bool, bool), LoadError> {
+    let mut pseudolocale = false;
+    let mut debug_locale = false;
+    for language in env::var("INCLUDE_LANGUAGES")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+    {
+        match language {
+            PSEUDO_LANGUAGE => pseudolocale = true,
+            DEBUG_LANGUAGE => debug_locale = true,
+            _ => {
+                return Err(LoadError::Config(format!(
+                    "Unknown generated language {language} in INCLUDE_LANGUAGES"
+                )))
+            }
+        }
+    }
+    Ok((pseudolocale, debug_locale))
+}
+
+pub(crate) fn default_language_from_env() -> std::result::Result<LanguageIdentifier, LoadError> {
+    let value = env::var("DEFAULT_LANG").unwrap_or("en_US".to_string());
+    value
+        .parse::<LanguageIdentifier>()
+        .map_err(|e| LoadError::Config(format!("Invalid default langauge {value}: {e}")))
+}
+
+/// Load all fluent resource files from a directory and returns them.
+/// Only files with an .ftl extension will be loaded, does not load files from subfolders
+///
+/// Files can be prefixed with a numeric load priority (`01-base.ftl`, `02-overrides.ftl`), resources are returned
+/// sorted by this priority (files without a prefix first) and then by name. The prefix is not part of the resource name
+///
+/// Generally you don't want to be using this but rather use the load function to get an
+/// LocalizationHolder with localizations for all your languages
+///
+/// However this is public for the purposes of generating bindings through the ... crate, if if you want to do it yourself
+/// # Arguments
+/// * `path` - A PathBuf to the folder to load the resources from
+/// * `pattern` - A glob pattern the file names have to match (like `ui-*.ftl`), `None` to load all of them
+///
+/// Files larger than [`DEFAULT_MAX_FILE_SIZE`] are reported as an error
+pub fn load_resources_from_folder(path: PathBuf, pattern: Option<&str>) -> Result<Vec<Resource>> {
+    let pattern = pattern.map(parse_file_pattern).transpose()?;
+    Ok(load_resources(
+        &path,
+        Normalization::None,
+        pattern.as_ref(),
+        DEFAULT_MAX_FILE_SIZE,
+        &[],
+    )?)
+}
+
+fn parse_file_pattern(pattern: &str) -> std::result::Result<glob::Pattern, LoadError> {
+    glob::Pattern::new(pattern)
+        .map_err(|e| LoadError::Config(format!("Invalid file pattern {pattern}: {e}")))
+}
+
+fn load_resources(
+    path: &Path,
+    normalization: Normalization,
+    file_pattern: Option<&glob::Pattern>,
+    max_file_size: u64,
+    cached: &[Resource],
+) -> std::result::Result<Vec<Resource>, LoadError> {
+    let (resources, errors) =
+        load_resources_partial(path, normalization, file_pattern, max_file_size, cached)?;
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(resources),
+    }
+}
+
+/// Load every resource in a directory that can be loaded, returning the problems with the others separately.
+/// Resources in `cached` are reused for files that weren't modified since they were loaded, files larger than `max_file_size` bytes are not read.
+/// Only fails as a whole if the directory itself can't be read
+pub(crate) fn load_resources_partial(
+    path: &Path,
+    normalization: Normalization,
+    file_pattern: Option<&glob::Pattern>,
+    max_file_size: u64,
+    cached: &[Resource],
+) -> std::result::Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+    trace!("Loading resources from {path:?}");
+    let path_name = path.to_string_lossy();
+    let mut files = Vec::new();
+
+    // Loop over all files in the directory and collect the ones we want to load
+    let lang_dir = read_dir(path, "localization directory")?;
+
+    for result in lang_dir {
+        let item_handle = result.map_err(|e| {
+            LoadError::io(
+                format!(
+                    "Failed to get a file handle when walking through the {path_name} directory"
+                ),
+                e,
+            )
+        })?;
+
+        let underlying_name = item_handle.file_name();
+        let name = underlying_name.to_string_lossy();
+        // Metadata rather than the file type of the entry, so symlinked files are followed
+        let meta = fs::metadata(item_handle.path()).map_err(|e| {
+            LoadError::io(
+                format!("Failed to get item metadata for {path_name}/{name}"),
+                e,
+            )
+        })?;
+
+        if !meta.is_file() {
+            debug!("Skipping {path_name}/{name} because it is not a file");
+            continue;
+        }
+
+        if !name.ends_with(FILE_EXTENSION) {
+            warn!("Skipping {path_name}/{name} because it doesn't have the proper {FILE_EXTENSION} extension");
+            continue;
+        }
+
+        if file_pattern.is_some_and(|pattern| !pattern.matches(&name)) {
+            trace!("Skipping {path_name}/{name} because it doesn't match the file pattern");
+            continue;
+        }
+
+        let (priority, stem) = split_priority(name.strip_suffix(FILE_EXTENSION).unwrap());
+        files.push((
+            priority,
+            stem.to_string(),
+            name.to_string(),
+            (item_handle.path(), meta.modified().ok(), meta.len()),
+        ));
+    }
+
+    sort_by_priority(&mut files);
+
+    let mut loaded = Vec::new();
+    let mut errors = Vec::new();
+    for (priority, stem, name, (file_path, modified_at, size)) in files {
+        if let Some(resource) = cached.iter().find(|resource| {
+            resource.file_name == name
+                && modified_at.is_some()
+                && resource.modified_at == modified_at
+        }) {
+            trace!("Reusing {path_name}/{name}, it wasn't modified since it was loaded");
+            loaded.push(resource.clone());
+            continue;
+        }
+
+        if size > max_file_size {
+            errors.push(LoadError::TooLarge {
+                file: format!("{path_name}/{name}"),
+                size,
+                limit: max_file_size,
+            });
+            continue;
+        }
+
+        trace!("Loading localization file {path_name}/{name}");
+        let file_content = match fs::read_to_string(file_path) {
+            Ok(content) => content,
+            Err(e) => {
+                errors.push(LoadError::io(
+                    format!("Failed to load localization file {path_name}/{name}"),
+                    e,
+                ));
+                continue;
+            }
+        };
+
+        match parse_resource(
+            &format!("{path_name}/{name}"),
+            priority,
+            stem,
+            name,
+            file_content,
+            normalization,
+        ) {
+            Ok(resource) => loaded.push(Resource {
+                modified_at,
+                ..resource
+            }),
+            Err(e) => errors.push(e),
+        }
+    }
+
+    Ok((loaded, errors))
+}
+
+/// Directory order is source defined, sort files by priority and name so overrides are applied in a stable order
+pub(crate) fn sort_by_priority<T>(files: &mut [(Option<u32>, String, String, T)]) {
+    files.sort_unstable_by(|(a_priority, a_name, _, _), (b_priority, b_name, _, _)| {
+        a_priority.cmp(b_priority).then_with(|| a_name.cmp(b_name))
+    });
+}
+
+/// Turn the content of a localization file into a resource, `path_name` is only used to refer to the file in logs and errors
+pub(crate) fn parse_resource(
+    path_name: &str,
+    priority: Option<u32>,
+    stem: String,
+    name: String,
+    mut file_content: String,
+    normalization: Normalization,
+) -> std::result::Result<Resource, LoadError> {
+    // Editors like notepad like to save with a BOM, fluent would treat this as junk
+    if let Some(stripped) = file_content.strip_prefix(BYTE_ORDER_MARK) {
+        debug!("Stripped byte order mark from {path_name}, consider saving it without one");
+        file_content = stripped.to_string();
+    }
+    file_content = normalization.apply(file_content);
+
+    if file_content.trim().is_empty() {
+        trace!("{path_name} is empty, loading it as an empty resource");
+        file_content.clear();
+    }
+
+    let fluent_resource = match FluentResource::try_new(file_content.clone()) {
+        Ok(resource) => resource,
+        Err((_, error_list)) => {
+            return Err(LoadError::parse(
+                path_name.to_string(),
+                &file_content,
+                error_list,
+            ));
+        }
+    };
+
+    Ok(Resource {
+        name: stem,
+        file_name: name,
+        resource: Arc::new(fluent_resource),
+        priority,
+        modified_at: None,
+    })
+}
+
+/// A copy of a message or term without its comment, other entries are not expected
+pub(crate) fn without_comment<'s>(entry: &Entry<&'s str>) -> Entry<&'s str> {
+    match entry {
+        Entry::Message(message) => Entry::Message(ast::Message {
+            comment: None,
+            ..message.clone()
+        }),
+        Entry::Term(term) => Entry::Term(ast::Term {
+            comment: None,
+            ..term.clone()
+        }),
+        _ => unreachable!("Only messages and terms are expected"),
+    }
+}
+
+/// Open a directory for walking, a missing directory is a configuration error rather than an io one
+fn read_dir(path: &Path, description: &str) -> std::result::Result<fs::ReadDir, LoadError> {
+    fs::read_dir(path).map_err(|e| {
+        let path_name = path.to_string_lossy();
+        if e.kind() == io::ErrorKind::NotFound {
+            LoadError::Missing {
+                description: description.to_string(),
+                path: path.to_path_buf(),
+            }
+        } else {
+            LoadError::io(format!("Failed to read {description} {path_name}"), e)
+        }
+    })
+}
+
+/// Split the optional numeric load priority off a file stem, `01-base` yields `(Some(1), "base")` and `10-overrides` `(Some(10), "overrides")`.
+/// Priorities are compared as numbers, so `2-x` loads before `10-y`
+pub(crate) fn split_priority(stem: &str) -> (Option<u32>, &str) {
+    match stem.split_once('-') {
+        Some((prefix, rest))
+            if !prefix.is_empty()
+                && !rest.is_empty()
+                && prefix.bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            match prefix.parse() {
+                Ok(priority) => (Some(priority), rest),
+                Err(_) => (None, stem),
+            }
+        }
+        _ => (None, stem),
+    }
+}
+
+/// Names of all messages with a value in the resources
+fn message_names(resources: &[Resource]) -> HashSet<String> {
+    resources
+        .iter()
+        .flat_map(|resource| resource.resource.entries())
+        .filter_map(|entry| match entry {
+            Entry::Message(message) => Some(message.id.name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The value of a message, or one of its attributes when the name is a `message.attribute` key
+fn find_pattern<'b>(
+    bundle: &'b FluentBundle,
+    name: &str,
+) -> std::result::Result<&'b Pattern<&'b str>, LocalizeError> {
+    let (message_name, attribute) = match name.split_once('.') {
+        Some((message_name, attribute)) => (message_name, Some(attribute)),
+        None => (name, None),
+    };
+    let message = bundle
+        .get_message(message_name)
+        .ok_or_else(|| LocalizeError::MissingMessage(name.to_string()))?;
+
+    match attribute {
+        Some(attribute) => message
+            .get_attribute(attribute)
+            .map(|attribute| attribute.value())
+            .ok_or_else(|| LocalizeError::MissingAttribute(name.to_string())),
+        None => message
+            .value()
+            .ok_or_else(|| LocalizeError::MissingValue(name.to_string())),
+    }
+}
+
+fn add_function(
+    bundle: &mut FluentBundle,
+    name: &str,
+    function: LocalizationFunction,
+) -> std::result::Result<(), FluentError> {
+    bundle.add_function(name, move |positional, named| function(positional, named))
+}
+
+impl Generation {
+    /// Build the bundle for a language and add it, returns the problems found with its resources.
+    /// The language is added even if there are problems, with later files overriding any duplicate keys.
+    /// A loaded language made up of the same resources keeps its bundle if the settings allow it
+    fn add_language(
+        &mut self,
+        lang_name: String,
+        identifier: LanguageIdentifier,
+        defaults: &[Resource],
+        resources: Vec<Resource>,
+        settings: &BundleSettings,
+    ) -> Vec<LoadError> {
+        let layers: Vec<Arc<FluentResource>> = defaults
+            .iter()
+            .chain(resources.iter())
+            .map(|resource| resource.resource.clone())
+            .collect();
+        if settings.reuse_bundles && self.unchanged(&lang_name, &layers) {
+            trace!("Keeping the bundle of {lang_name}, its resources didn't change");
+            self.resources.insert(lang_name, resources);
+            return Vec::new();
+        }
+
+        self.own_messages
+            .insert(lang_name.clone(), message_names(&resources));
+        self.resources.insert(lang_name.clone(), resources.clone());
+        self.layers.insert(lang_name.clone(), layers);
+        let (mut bundle, errors) = load_bundle(
+            &lang_name,
+            identifier,
+            defaults,
+            resources,
+            settings.strict_duplicates,
+        );
+        for (name, function) in settings.functions {
+            add_function(&mut bundle, name, function.clone())
+                .expect("Registered functions are only added to fresh bundles");
+        }
+        #[cfg(feature = "collation")]
+        if let Some(collator) = collation::collator_for(&lang_name) {
+            self.collators.insert(lang_name.clone(), Arc::new(collator));
+        }
+
+        // finally add the bundle to the map
+        self.bundles.insert(lang_name, Arc::new(bundle));
+
+        errors
+    }
+
+    /// Add resources on top of a loaded language, overriding what it already has
+    fn add_layers(
+        &mut self,
+        language: &str,
+        resources: Vec<Arc<FluentResource>>,
+        functions: &[(String, LocalizationFunction)],
+    ) {
+        let Some(bundle) = self.exclusive_bundle(language, functions) else {
+            return;
+        };
+        for resource in &resources {
+            bundle.add_resource_overriding(resource.clone());
+        }
+        self.layers
+            .entry(language.to_string())
+            .or_default()
+            .extend(resources);
+    }
+
+    /// The language that is used for a language, the first loaded one of its fallback chain
+    fn resolved_language<'g>(&'g self, language: &'g str) -> &'g str {
+        if self.bundles.contains_key(language) {
+            return language;
+        }
+
+        self.fallback_chain(language)
+            .first()
+            .copied()
+            .unwrap_or(&self.default_language)
+    }
+
+    /// See [`LocalizationHolder::fallback_chain`]
+    fn fallback_chain(&self, language: &str) -> Vec<&str> {
+        let base_language = language
+            .parse::<LanguageIdentifier>()
+            .ok()
+            .map(|identifier| identifier.language.to_string());
+
+        let mut chain: Vec<&str> = Vec::new();
+        for candidate in [
+            Some(language),
+            base_language.as_deref(),
+            Some(&self.default_language),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some((loaded, _)) = self.bundles.get_key_value(candidate) {
+                if !chain.contains(&loaded.as_str()) {
+                    chain.push(loaded);
+                }
+            }
+        }
+
+        chain
+    }
+
+    /// The bundle for a language, or the first one it falls back to that is loaded
+    fn bundle(&self, language: &str) -> &Arc<FluentBundle> {
+        self.bundles
+            .get(self.resolved_language(language))
+            .unwrap_or_else(|| &self.bundles[&self.default_language])
+    }
+
+    /// Whether the language defines the message itself, see [`LocalizationHolder::has_own_message`]
+    fn has_own_message(&self, language: &str, name: &str) -> bool {
+        // Attributes come with the message they belong to
+        let name = name.split_once('.').map_or(name, |(message, _)| message);
+
+        self.own_messages
+            .get(language)
+            .is_some_and(|messages| messages.contains(name))
+    }
+
+    /// The messages and terms a loaded language ended up with after all overrides, by key with terms prefixed with `-`
+    pub(crate) fn effective_entries(&self, language: &str) -> HashMap<String, &Entry<&str>> {
+        // Later layers override earlier ones, so the first entry found from the back wins
+        let mut entries: HashMap<String, &Entry<&str>> = HashMap::new();
+        for entry in self
+            .layers
+            .get(language)
+            .into_iter()
+            .flatten()
+            .rev()
+            .flat_map(|resource| resource.entries())
+        {
+            let key = match entry {
+                Entry::Message(message) => message.id.name.to_string(),
+                Entry::Term(term) => format!("-{}", term.id.name),
+                _ => continue,
+            };
+            entries.entry(key).or_insert(entry);
+        }
+
+        entries
+    }
+
+    /// The bundle of a loaded language to change in place. A bundle that is still shared (with an earlier generation someone holds on to,
+    /// or with another language made up of the same files) is built again from its layers first, so the change doesn't reach the others
+    fn exclusive_bundle(
+        &mut self,
+        language: &str,
+        functions: &[(String, LocalizationFunction)],
+    ) -> Option<&mut FluentBundle> {
+        let bundle = self.bundles.get_mut(language)?;
+        if Arc::get_mut(bundle).is_none() {
+            trace!("Building the bundle of {language} again, it is shared");
+            let mut rebuilt = FluentBundle::new_concurrent(bundle.locales.clone());
+            for layer in self.layers.get(language).into_iter().flatten() {
+                rebuilt.add_resource_overriding(layer.clone());
+            }
+            for (name, function) in functions {
+                add_function(&mut rebuilt, name, function.clone())
+                    .expect("Registered functions are only added to fresh bundles");
+            }
+            *bundle = Arc::new(rebuilt);
+        }
+
+        Arc::get_mut(bundle)
+    }
+
+    /// Whether a loaded language is made up of exactly these resources. Files that didn't change are not parsed again and
+    /// keep their resource, so this compares pointers rather than content
+    fn unchanged(&self, language: &str, layers: &[Arc<FluentResource>]) -> bool {
+        self.layers.get(language).is_some_and(|loaded| {
+            loaded.len() == layers.len()
+                && loaded.iter().zip(layers).all(|(a, b)| Arc::ptr_eq(a, b))
+        })
+    }
+
+    /// Unload a language, returns whether it was loaded. The default language can't be removed
+    fn remove_language(&mut self, language: &str) -> bool {
+        if language == self.default_language {
+            return false;
+        }
+
+        self.layers.remove(language);
+        self.own_messages.remove(language);
+        self.resources.remove(language);
+        #[cfg(feature = "collation")]
+        self.collators.remove(language);
+        self.bundles.remove(language).is_some()
+    }
+}
+
+/// How bundles are built, taken from the holder
+#[derive(Clone, Copy)]
+struct BundleSettings<'h> {
+    strict_duplicates: bool,
+    reuse_bundles: bool,
+    functions: &'h [(String, LocalizationFunction)],
+}
+
+/// Text formatted from a generation, which can be replaced as soon as it is formatted
+fn owned(text: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+/// Assemble the bundle for a language, returns the duplicate key problems found along the way
+fn load_bundle(
+    lang_name: &str,
+    identifier: LanguageIdentifier,
+    defaults: &[Resource],
+    resources: Vec<Resource>,
+    strict_duplicates: bool,
+) -> (FluentBundle, Vec<LoadError>) {
+    trace!("Loading language {lang_name}");
+
+    let mut bundle = FluentBundle::new_concurrent(Vec::from_iter([identifier.clone()]));
+
+    let errors = if strict_duplicates {
+        duplicate_errors(lang_name, &identifier, &resources)
+    } else {
+        Vec::new()
+    };
+
+    for default in defaults {
+        bundle.add_resource_overriding(default.resource.clone())
+    }
+
+    for resource in resources {
+        bundle.add_resource_overriding(resource.resource)
+    }
+
+    (bundle, errors)
+}
+
+/// Test the resources for keys that are defined more than once.
+/// Files with a different priority are allowed to override each other, only files of the same priority are tested against each other
+fn duplicate_errors(
+    lang_name: &str,
+    identifier: &LanguageIdentifier,
+    resources: &[Resource],
+) -> Vec<LoadError> {
+    let mut test_bundle = None;
+    let mut test_priority = None;
+    // The file every key of the current priority was first defined in
+    let mut defined_in: HashMap<String, String> = HashMap::new();
+    let mut errors = Vec::new();
+
+    for resource in resources {
+        if test_bundle.is_none() || resource.priority != test_priority {
+            test_bundle = Some(FluentBundle::new_concurrent(Vec::from_iter([
+                identifier.clone()
+            ])));
+            test_priority = resource.priority;
+            defined_in.clear();
+        }
+        let file = format!("{lang_name}/{}", resource.file_name);
+
+        // The test bundle does not have defaults, so we get errors if there are duplicate keys across the files (shouldn't happen, but ya know. me proofing)
+        if let Some(Err(error_list)) = test_bundle
+            .as_mut()
+            .map(|test_bundle| test_bundle.add_resource(resource.resource.clone()))
+        {
+            let keys = error_list
+                .iter()
+                .filter_map(|e| match e {
+                    // The kind is only exposed through its name
+                    FluentError::Overriding { kind, id } => {
+                        let key = match kind.to_string().as_str() {
+                            "term" => format!("-{id}"),
+                            _ => id.clone(),
+                        };
+                        let first = defined_in.get(&key).cloned().unwrap_or_default();
+                        Some((key, first))
+                    }
+                    _ => None,
+                })
+                .collect();
+            errors.push(LoadError::Duplicate {
+                file: file.clone(),
+                errors: fold_displayable(
+                    error_list
+                        .into_iter()
+                        // This should only ever yield overriding errors so lets keep this simple
+                        .map(|e| e.to_string()),
+                    "\n-----\n",
+                ),
+                keys,
+            });
+        }
+        for entry in resource.resource.entries() {
+            let key = match entry {
+                Entry::Message(message) => message.id.name.to_string(),
+                Entry::Term(term) => format!("-{}", term.id.name),
+                _ => continue,
+            };
+            defined_in.entry(key).or_insert_with(|| file.clone());
+        }
+    }
+
+    errors
+}
+
+/// Remove the unicode isolation marks fluent puts around placeables, for output that doesn't handle them well like plain text logs.
+/// Every bundle uses isolation so right-to-left arguments display correctly, so only strip them where that doesn't matter
+pub fn strip_isolation_marks(text: Cow<str>) -> Cow<str> {
+    if !text.contains([FIRST_STRONG_ISOLATE, POP_DIRECTIONAL_ISOLATE]) {
+        return text;
+    }
+
+    Cow::Owned(
+        text.chars()
+            .filter(|c| *c != FIRST_STRONG_ISOLATE && *c != POP_DIRECTIONAL_ISOLATE)
+            .collect(),
+    )
+}
+
+#[doc(hidden)]
+pub fn fold_displayable(
+    mut iterator: impl Iterator<Item = impl Display>,
+    separator: impl Display,
+) -> String {
+    let Some(first) = iterator.next() else {
+        return String::new();
+    };
+    iterator.fold(first.to_string(), |mut assembled, new| {
+        // Writing into a String can't fail
+        let _ = write!(assembled, "{separator}{new}");
+        assembled
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    fn source(greeting: &str) -> InMemorySource {
+        InMemorySource::builder()
+            .default_files([("base", "greeting = Hello")])
+            .language("en-US", [("base", "greeting = Hello")])
+            .language("nl", [("base", greeting)])
+            .build()
+    }
+
+    fn holder() -> LocalizationHolder {
+        LocalizationHolder::load_from(&source("greeting = Hallo"), &"en-US".parse().unwrap())
+            .unwrap()
+    }
+
+    fn greeting(holder: &LocalizationHolder, language: &str) -> String {
+        holder
+            .format_message(language, "greeting", None, &mut Vec::new())
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn byte_order_marks_and_blank_files_load() {
+        let source = InMemorySource::builder()
+            .language(
+                "en-US",
+                [
+                    ("base", "\u{feff}greeting = Hello"),
+                    ("blank", " \n\t\n"),
+                    ("empty", ""),
+                ],
+            )
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+
+        // Left in, the mark would have turned the first message into junk
+        assert!(holder.get_bundle("en-US").has_message("greeting"));
+        assert_eq!(greeting(&holder, "en-US"), "Hello");
+    }
+
+    #[test]
+    fn missing_directory_is_not_an_author_error() {
+        let config = LoaderConfig::new(
+            env::temp_dir().join("fluent-localization-does-not-exist"),
+            "en-US".parse().unwrap(),
+        );
+        let error = LocalizationHolder::load_with_config(&config).unwrap_err();
+
+        assert!(matches!(error, LoadError::Missing { .. }));
+        assert!(!error.is_author_error());
+    }
+
+    #[test]
+    fn broken_file_is_an_author_error() {
+        let source = InMemorySource::builder()
+            .language("en-US", [("base", "greeting = Hello\n= broken")])
+            .build();
+        let error = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap_err();
+
+        assert!(matches!(&error, LoadError::Parse { file, .. } if file == "en-US/base.ftl"));
+        assert!(error.is_author_error());
+        assert!(matches!(
+            error.variants().as_slice(),
+            [LocalizationLoadingError::ParseError {
+                line: 2,
+                col: 1,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn shared_holder_swaps_in_loaded_language() {
+        let holder = Arc::new(holder());
+        let before = holder
+            .format_message("nl", "greeting", None, &mut Vec::new())
+            .unwrap();
+
+        let loader = Arc::clone(&holder);
+        thread::spawn(move || {
+            loader
+                .load_language_from(&source("greeting = Goedendag"), "nl")
+                .unwrap()
+        })
+        .join()
+        .unwrap();
+
+        // Formatted from the languages that were swapped out
+        assert_eq!(before, "Hallo");
+        assert_eq!(greeting(&holder, "nl"), "Goedendag");
+    }
+
+    fn duplicated() -> InMemorySource {
+        InMemorySource::builder()
+            .language(
+                "nl",
+                [("base", "greeting = Hoi"), ("extra", "greeting = Hey")],
+            )
+            .build()
+    }
+
+    #[test]
+    fn failed_load_keeps_previous_language() {
+        let holder = holder();
+
+        assert!(holder.load_language_from(&duplicated(), "nl").is_err());
+        assert_eq!(greeting(&holder, "nl"), "Hallo");
+    }
+
+    #[test]
+    fn strict_duplicates_reports_keys_defined_twice() {
+        let mut holder = holder();
+        let error = holder.load_language_from(&duplicated(), "nl").unwrap_err();
+        assert!(matches!(
+            &error,
+            LoadError::Duplicate { keys, .. } if keys.iter().any(|(key, _)| key == "greeting")
+        ));
+
+        // Without the check the later file wins
+        holder.set_strict_duplicates(false);
+        holder.load_language_from(&duplicated(), "nl").unwrap();
+        assert_eq!(greeting(&holder, "nl"), "Hey");
+    }
+
+    #[test]
+    fn default_language_changes_through_shared_reference() {
+        let holder = holder();
+        holder.set_default_language("nl").unwrap();
+
+        assert_eq!(holder.default_language(), "nl");
+        assert!(!holder.remove_language("nl"));
+        assert!(holder.remove_language("en-US"));
+        assert_eq!(holder.languages().collect::<Vec<_>>(), ["nl"]);
+        assert_eq!(greeting(&holder, "en-US"), "Hallo");
+    }
+
+    fn directory(name: &str) -> PathBuf {
+        let base =
+            env::temp_dir().join(format!("fluent-localization-{name}-{}", std::process::id()));
+        for (language, content) in [
+            (DEFAULT_DIR, "greeting = Hello"),
+            ("en-US", "greeting = Hello"),
+            ("nl", "greeting = Hallo"),
+        ] {
+            fs::create_dir_all(base.join(language)).unwrap();
+            fs::write(base.join(language).join("base.ftl"), content).unwrap();
+        }
+        base
+    }
+
+    fn load_directory(name: &str, reuse_bundles: bool) -> LocalizationHolder {
+        LocalizationHolder::builder()
+            .base_path(directory(name))
+            .default_language("en-US".parse().unwrap())
+            .reuse_bundles(reuse_bundles)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn reload_keeps_unchanged_bundles() {
+        let holder = load_directory("reuse", true);
+        let before = holder.get_bundle("nl");
+        holder.reload().unwrap();
+
+        let stats = holder.bundle_stats();
+        assert_eq!(stats.bundles, stats.languages);
+        assert!(Arc::ptr_eq(&before, &holder.get_bundle("nl")));
+    }
+
+    #[test]
+    fn reload_without_reuse_frees_replaced_bundles() {
+        let holder = Arc::new(load_directory("rebuild", false));
+        let before = holder.get_bundle("nl");
+        for _ in 0..3 {
+            holder.reload().unwrap();
+        }
+
+        // Only the bundle still held here outlives the languages it was replaced with
+        let stats = holder.bundle_stats();
+        assert_eq!(stats.bundles, stats.languages);
+        assert!(!Arc::ptr_eq(&before, &holder.get_bundle("nl")));
+        assert_eq!(Arc::strong_count(&before), 1);
+        assert!(before.has_message("greeting"));
+        assert_eq!(greeting(&holder, "nl"), "Hallo");
+    }
+
+    #[test]
+    fn merging_into_a_bundle_in_use_builds_it_again() {
+        let mut holder = holder();
+        holder
+            .load_language_from(&source("greeting = Goedendag"), "nl")
+            .unwrap();
+        let before = holder.get_bundle("nl");
+        let plugin = InMemorySource::builder()
+            .language(
+                "nl",
+                [("plugin", "greeting = Hoi\nplugin-name = Uitbreiding")],
+            )
+            .build();
+
+        holder
+            .merge_resources("nl", plugin.language_resources("nl").unwrap())
+            .unwrap();
+        assert_eq!(greeting(&holder, "nl"), "Hoi");
+        // The bundle that was handed out doesn't change underneath its user
+        assert!(!before.has_message("plugin-name"));
+    }
+
+    #[test]
+    fn merged_resources_layer_over_languages() {
+        let mut holder = holder();
+        let plugin = InMemorySource::builder()
+            .language("nl", [("plugin", "plugin-name = Uitbreiding")])
+            .language("de", [("plugin", "plugin-name = Erweiterung")])
+            .build();
+
+        holder
+            .merge_resources("nl", plugin.language_resources("nl").unwrap())
+            .unwrap();
+        assert_eq!(greeting(&holder, "nl"), "Hallo");
+        let plugin_name = holder.format_message("nl", "plugin-name", None, &mut Vec::new());
+        assert_eq!(plugin_name.as_deref(), Some("Uitbreiding"));
+
+        // A language that wasn't loaded is created on top of the default language
+        holder
+            .merge_resources("de", plugin.language_resources("de").unwrap())
+            .unwrap();
+        assert_eq!(greeting(&holder, "de"), "Hello");
+        let plugin_name = holder.format_message("de", "plugin-name", None, &mut Vec::new());
+        assert_eq!(plugin_name.as_deref(), Some("Erweiterung"));
+
+        // Resources that contradict each other are refused as a whole
+        let error = holder
+            .merge_resources("nl", duplicated().language_resources("nl").unwrap())
+            .unwrap_err();
+        assert!(matches!(error, LoadError::Duplicate { .. }));
+        assert_eq!(greeting(&holder, "nl"), "Hallo");
+    }
+
+    #[test]
+    fn only_own_messages_are_translated() {
+        let source = InMemorySource::builder()
+            .default_files([("base", "greeting = Hello\nlegal = Terms")])
+            .language("en-US", [("base", "greeting = Hello\nlegal = Terms")])
+            .language("nl", [("base", "greeting = Hallo")])
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+
+        assert!(holder.translates_message("nl", "greeting"));
+        // Filled in from the default files, which is exactly what `@no-fallback` messages must not be
+        assert!(!holder.translates_message("nl", "legal"));
+        // Regions count what their language translates, languages that end up at the default language don't fall back
+        assert!(holder.translates_message("nl-BE", "greeting"));
+        assert!(!holder.translates_message("nl-BE", "legal"));
+        assert!(holder.translates_message("en-GB", "legal"));
+    }
+
+    #[test]
+    fn collecting_errors_reports_every_broken_language() {
+        let source = InMemorySource::builder()
+            .language("en-US", [("base", "greeting = Hello")])
+            .language("nl", [("base", "greeting = Hallo\n= broken")])
+            .language(
+                "fr",
+                [("base", "= broken"), ("extra", "greeting = Bonjour")],
+            )
+            .build();
+        let (holder, errors) =
+            LocalizationHolder::load_from_collecting_errors(&source, &"en-US".parse().unwrap());
+
+        let mut files: Vec<&str> = errors
+            .iter()
+            .filter_map(|error| match error {
+                LoadError::Parse { file, .. } => Some(file.as_str()),
+                _ => None,
+            })
+            .collect();
+        files.sort_unstable();
+        assert_eq!(files, ["fr/base.ftl", "nl/base.ftl"]);
+        assert_eq!(errors.len(), 2);
+
+        // Only the broken files are left out
+        let holder = holder.unwrap();
+        assert_eq!(greeting(&holder, "en-US"), "Hello");
+        assert_eq!(greeting(&holder, "fr"), "Bonjour");
+    }
+
+    #[cfg(feature = "include_dir")]
+    #[test]
+    fn embedded_directory_loads_like_a_directory() {
+        static EMBEDDED: include_dir::Dir =
+            include_dir::include_dir!("$CARGO_MANIFEST_DIR/test-data/embedded");
+        let holder =
+            LocalizationHolder::from_include_dir(&EMBEDDED, &"en-US".parse().unwrap()).unwrap();
+
+        assert_eq!(greeting(&holder, "en-US"), "Hello");
+        assert_eq!(greeting(&holder, "nl"), "Hallo");
+        let farewell = holder.format_message("nl", "farewell", None, &mut Vec::new());
+        assert_eq!(farewell.as_deref(), Some("Tot ziens"));
+    }
+
+    #[test]
+    fn dump_shows_what_won() {
+        let source = InMemorySource::builder()
+            .default_files([("base", "greeting = Hello\nlegal = Terms")])
+            .language("en-US", [("base", "greeting = Hello\nlegal = Terms")])
+            .language(
+                "nl",
+                [
+                    ("base", "# Overridden\ngreeting = Hallo\n-brand = Fluent"),
+                    ("01-overrides", "greeting = Hoi { -brand }"),
+                ],
+            )
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            holder.dump("nl"),
+            "-brand = Fluent\ngreeting = Hoi { -brand }\nlegal = Terms\n"
+        );
+        // Regions dump the language they resolve to
+        assert_eq!(holder.dump("nl-BE"), holder.dump("nl"));
+    }
+
+    #[test]
+    fn terms_only_take_variables_from_their_arguments() {
+        let source = InMemorySource::builder()
+            .default_files([(
+                "base",
+                "-brand = { $casing ->\n    [upper] FLUENT\n   *[lower] fluent\n}\n\
+                 literal = { -brand(casing: \"upper\") }\n\
+                 positional = { -brand($style) }\n\
+                 bare = { -brand }\n\
+                 welcome = Welcome { $user }\n\
+                 nested = { welcome } { $other }",
+            )])
+            .build();
+        let variables = message_variables(&source.default_resources().unwrap());
+        let of = |key: &str| {
+            variables[key]
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(of("literal").is_empty());
+        assert_eq!(of("positional"), ["style"]);
+        assert!(of("bare").is_empty());
+        assert_eq!(of("nested"), ["other", "user"]);
+    }
+
+    #[test]
+    fn localize_in_all_languages() {
+        let source = InMemorySource::builder()
+            .default_files([("base", "welcome = Welcome { $user }")])
+            .language("en-US", [("base", "welcome = Welcome { $user }")])
+            .language("nl", [("base", "welcome = Welkom { $user }")])
+            .language("de", [("base", "farewell = Tschüss")])
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+        let mut arguments = FluentArgs::new();
+        arguments.set("user", "Ada");
+
+        let localized = holder.localize_in_all("welcome", Some(&arguments), true);
+        let localized: Vec<(&str, String)> = localized
+            .iter()
+            .map(|(language, text)| {
+                (
+                    language.as_str(),
+                    strip_isolation_marks(text.into()).into_owned(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            localized,
+            [
+                ("de", "Welcome Ada".to_string()),
+                ("en-US", "Welcome Ada".to_string()),
+                ("nl", "Welkom Ada".to_string()),
+            ]
+        );
+
+        let translated = holder.localize_in_all("welcome", Some(&arguments), false);
+        assert_eq!(translated.keys().collect::<Vec<_>>(), ["en-US", "nl"]);
+        assert!(holder.localize_in_all("missing", None, true).is_empty());
+    }
+
+    #[test]
+    fn files_over_the_size_limit_are_reported() {
+        let load = |limit| {
+            LocalizationHolder::builder()
+                .base_path(directory("too-large"))
+                .default_language("en-US".parse().unwrap())
+                .max_file_size(limit)
+                .build()
+        };
+
+        let error = load(8).unwrap_err();
+        assert!(error.is_author_error());
+        assert!(matches!(
+            error,
+            LoadError::TooLarge { file, size: 16, limit: 8 } if file.ends_with("base.ftl")
+        ));
+        // A file of exactly the limit is fine
+        assert_eq!(greeting(&load(16).unwrap(), "nl"), "Hallo");
+    }
+
+    #[test]
+    fn empty_messages_show_their_key_when_enabled() {
+        let source = InMemorySource::builder()
+            .language(
+                "en-US",
+                [(
+                    "base",
+                    "-unset = { \"\" }\nblank = { -unset }\nnothing = { \"\" }\ngreeting = Hello",
+                )],
+            )
+            .build();
+        let mut holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+        let format = |holder: &LocalizationHolder, name| {
+            holder
+                .format_message("en-US", name, None, &mut Vec::new())
+                .unwrap()
+                .into_owned()
+        };
+        assert_eq!(format(&holder, "blank"), "");
+
+        holder.set_show_key_on_empty(true);
+        assert_eq!(format(&holder, "blank"), "⟦blank⟧");
+        assert_eq!(
+            holder.try_format_message("en-US", "blank", None).unwrap(),
+            "⟦blank⟧"
+        );
+        // Deliberately empty messages and messages with text are left alone
+        assert_eq!(format(&holder, "nothing"), "");
+        assert_eq!(format(&holder, "greeting"), "Hello");
+        // Missing messages are still missing
+        assert!(holder
+            .format_message("en-US", "missing", None, &mut Vec::new())
+            .is_none());
+    }
+
+    #[test]
+    fn select_discrepancies_report_dropped_and_invented_variants() {
+        let source = InMemorySource::builder()
+            .language(
+                "en-US",
+                [(
+                    "base",
+                    "inbox = { $count ->\n    [one] One message\n   *[other] Messages\n}\n\
+                     greeting = { $gender ->\n    [male] Hi sir\n    [female] Hi madam\n   *[other] Hi\n}",
+                )],
+            )
+            // Plural categories differ between languages, so the extra `zero` and `0` are fine
+            .language(
+                "nl",
+                [(
+                    "base",
+                    "inbox = { $count ->\n    [0] Geen berichten\n    [zero] Niets\n   *[other] Berichten\n}\n\
+                     greeting = { $gender ->\n    [male] Hoi meneer\n   *[female] Hoi mevrouw\n}",
+                )],
+            )
+            .language(
+                "de",
+                [(
+                    "base",
+                    "greeting = { $gender ->\n    [male] Hallo Herr\n    [female] Hallo Frau\n    [divers] Hallo\n   *[other] Hallo\n}",
+                )],
+            )
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+
+        assert_eq!(
+            holder.select_discrepancies(),
+            [
+                SelectDiscrepancy {
+                    key: "greeting".to_string(),
+                    language: "de".to_string(),
+                    variable: "gender".to_string(),
+                    added: vec!["divers".to_string()],
+                    missing: Vec::new(),
+                },
+                SelectDiscrepancy {
+                    key: "greeting".to_string(),
+                    language: "nl".to_string(),
+                    variable: "gender".to_string(),
+                    added: Vec::new(),
+                    missing: vec!["other".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_files_and_language_directories_load() {
+        use std::os::unix::fs::symlink;
+
+        let base = directory("symlinks");
+        let shared = env::temp_dir().join(format!(
+            "fluent-localization-symlinks-shared-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(shared.join("de")).unwrap();
+        fs::write(shared.join("de").join("base.ftl"), "greeting = Hallo Welt").unwrap();
+        fs::write(shared.join("extra.ftl"), "farewell = Tot ziens").unwrap();
+        for link in [base.join("de"), base.join("nl").join("extra.ftl")] {
+            let _ = fs::remove_file(link);
+        }
+        symlink(shared.join("de"), base.join("de")).unwrap();
+        symlink(shared.join("extra.ftl"), base.join("nl").join("extra.ftl")).unwrap();
+
+        let holder = LocalizationHolder::builder()
+            .base_path(base)
+            .default_language("en-US".parse().unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(greeting(&holder, "de"), "Hallo Welt");
+        assert!(holder.has_own_message("de", "greeting"));
+        let farewell = holder.format_message("nl", "farewell", None, &mut Vec::new());
+        assert_eq!(farewell.as_deref(), Some("Tot ziens"));
+        assert!(holder.has_own_message("nl", "farewell"));
+    }
+
+    #[test]
+    fn priorities_are_compared_as_numbers() {
+        assert_eq!(split_priority("01-base"), (Some(1), "base"));
+        assert_eq!(split_priority("404-errors"), (Some(404), "errors"));
+        assert_eq!(split_priority("base-errors"), (None, "base-errors"));
+        assert_eq!(split_priority("-errors"), (None, "-errors"));
+
+        let dir = env::temp_dir().join(format!(
+            "fluent-localization-priorities-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        for (file, content) in [
+            ("10-y.ftl", "greeting = Ten"),
+            ("2-x.ftl", "greeting = Two"),
+            ("base.ftl", "greeting = Base"),
+        ] {
+            fs::write(dir.join(file), content).unwrap();
+        }
+
+        let resources = load_resources_from_folder(dir, None).unwrap();
+        let order: Vec<(&str, Option<u32>)> = resources
+            .iter()
+            .map(|resource| (resource.file_name.as_str(), resource.priority))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("base.ftl", None),
+                ("2-x.ftl", Some(2)),
+                ("10-y.ftl", Some(10))
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_keys() {
+        let load = |dutch: &str| {
+            let source = InMemorySource::builder()
+                .default_files([("base", "greeting = Hello\nfarewell = Goodbye")])
+                .language("en-US", [("base", "greeting = Hello\nfarewell = Goodbye")])
+                .language("nl", [("base", dutch)])
+                .build();
+            LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap()
+        };
+        let before = load("greeting = Hallo\nfarewell = Doei\nname = Nederlands");
+        let after =
+            load("# Comments don't count\ngreeting = Hoi\nname = Nederlands\nwelcome = Welkom");
+
+        let diff = before.diff(&after);
+        let change = |key: &str, kind| KeyChange {
+            key: key.to_string(),
+            kind,
+        };
+        // `farewell` is still there in Dutch through the default resources, only its text changed
+        assert_eq!(
+            diff.language("nl"),
+            [
+                change("farewell", ChangeKind::Changed),
+                change("greeting", ChangeKind::Changed),
+                change("welcome", ChangeKind::Added),
+            ]
+        );
+        assert!(diff.language("en-US").is_empty());
+        assert_eq!(
+            after.diff(&before).language("nl")[2],
+            change("welcome", ChangeKind::Removed)
+        );
+        assert!(before
+            .diff(&load(
+                "greeting = Hallo\nfarewell = Doei\nname = Nederlands"
+            ))
+            .is_empty());
+    }
+
+    #[test]
+    fn parse_errors_report_the_line_they_are_on() {
+        let content =
+            "greeting = Hello\nfarewell = Goodbye\n\n# Notices\nnotice = { $user ! }\nlast = Last\n";
+        let (_, errors) = FluentResource::try_new(content.to_string()).unwrap_err();
+
+        let display = format_parse_error(content, &errors[0]);
+        assert_eq!(display.line, 5);
+        assert_eq!(display.line_text, "notice = { $user ! }");
+        assert_eq!(display.column, 17);
+        assert!(display
+            .to_string()
+            .contains(" at 5:17\n    notice = { $user ! }"));
+    }
+
+    #[cfg(feature = "collation")]
+    #[test]
+    fn collation_follows_the_language() {
+        let source = InMemorySource::builder()
+            .language("en-US", [("base", "greeting = Hello")])
+            .language("de", [("base", "greeting = Hallo")])
+            .language("sv", [("base", "greeting = Hej")])
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+        let words = || {
+            vec![
+                "zebra".to_string(),
+                "äpfel".to_string(),
+                "apfel".to_string(),
+            ]
+        };
+
+        // German sorts `ä` with `a`, Swedish after `z` like the bytes do
+        let mut german = words();
+        holder.sort_by_locale("de", &mut german);
+        assert_eq!(german, ["apfel", "äpfel", "zebra"]);
+        let mut swedish = words();
+        holder.sort_by_locale("sv", &mut swedish);
+        assert_eq!(swedish, ["apfel", "zebra", "äpfel"]);
+        assert_eq!(
+            holder.collate("de", "äpfel", "zebra"),
+            std::cmp::Ordering::Less
+        );
+        assert_eq!("äpfel".cmp("zebra"), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn nfc_normalization_composes_decomposed_text() {
+        let base = directory("normalization");
+        // An editor that stores decomposed text writes `e` followed by a combining accent
+        fs::write(base.join("nl").join("base.ftl"), "greeting = Cafe\u{301}").unwrap();
+        let load = |normalization| {
+            LocalizationHolder::builder()
+                .base_path(base.clone())
+                .default_language("en-US".parse().unwrap())
+                .normalization(normalization)
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(greeting(&load(Normalization::Nfc), "nl"), "Caf\u{e9}");
+        assert_eq!(greeting(&load(Normalization::None), "nl"), "Cafe\u{301}");
+        assert_eq!(greeting(&load(Normalization::Nfd), "nl"), "Cafe\u{301}");
+    }
+
+    #[test]
+    fn registered_functions_format_messages() {
+        fn shout<'a>(positional: &[FluentValue<'a>], _: &FluentArgs) -> FluentValue<'a> {
+            match positional {
+                [FluentValue::String(text)] => FluentValue::String(text.to_uppercase().into()),
+                _ => FluentValue::Error,
+            }
+        }
+        let source = InMemorySource::builder()
+            .language("en-US", [("base", "greeting = Hello { SHOUT($user) }")])
+            .build();
+        let mut holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+        holder.register_function("SHOUT", shout).unwrap();
+        assert!(holder.register_function("SHOUT", shout).is_err());
+
+        let mut arguments = FluentArgs::new();
+        arguments.set("user", "Ada");
+        let formatted = holder
+            .format_message("en-US", "greeting", Some(&arguments), &mut Vec::new())
+            .unwrap();
+        assert_eq!(formatted, "Hello \u{2068}ADA\u{2069}");
+
+        // Languages loaded after registering it get the function as well
+        let dutch = InMemorySource::builder()
+            .language("nl", [("base", "greeting = Hallo { SHOUT($user) }")])
+            .build();
+        holder.load_language_from(&dutch, "nl").unwrap();
+        let formatted = holder
+            .format_message("nl", "greeting", Some(&arguments), &mut Vec::new())
+            .unwrap();
+        assert_eq!(formatted, "Hallo \u{2068}ADA\u{2069}");
+    }
+
+    #[test]
+    fn fallback_chain_drops_the_region_before_the_default() {
+        let source = InMemorySource::builder()
+            .language("en-US", [("base", "greeting = Hello")])
+            .language("nl", [("base", "greeting = Hallo")])
+            .language("nl-BE", [("base", "greeting = Dag")])
+            .build();
+        let holder = LocalizationHolder::load_from(&source, &"en-US".parse().unwrap()).unwrap();
+
+        assert_eq!(holder.fallback_chain("nl-BE"), ["nl-BE", "nl", "en-US"]);
+        assert_eq!(holder.fallback_chain("nl"), ["nl", "en-US"]);
+        assert_eq!(holder.fallback_chain("en-US"), ["en-US"]);
+        // Languages that aren't loaded are skipped, unknown ones only have the default language
+        assert_eq!(holder.fallback_chain("nl-NL"), ["nl", "en-US"]);
+        assert_eq!(holder.fallback_chain("fr"), ["en-US"]);
+        assert_eq!(holder.fallback_chain("not a language"), ["en-US"]);
+    }
+
+    #[test]
+    fn file_patterns_only_load_matching_files() {
+        let base = directory("file-pattern");
+        for language in [DEFAULT_DIR, "en-US", "nl"] {
+            for (file, content) in [
+                ("ui-buttons.ftl", "ok = OK"),
+                ("ui-menus.ftl", "quit = Quit"),
+                ("admin.ftl", "ban = Ban"),
+                ("ui-notes.txt", "not = fluent"),
+            ] {
+                fs::write(base.join(language).join(file), content).unwrap();
+            }
+        }
+
+        let resources = load_resources_from_folder(base.join("nl"), Some("ui-*.ftl")).unwrap();
+        let names: Vec<&str> = resources
+            .iter()
+            .map(|resource| resource.file_name.as_str())
+            .collect();
+        assert_eq!(names, ["ui-buttons.ftl", "ui-menus.ftl"]);
+
+        let holder = LocalizationHolder::builder()
+            .base_path(base)
+            .default_language("en-US".parse().unwrap())
+            .file_pattern("ui-*.ftl")
+            .build()
+            .unwrap();
+        assert!(holder.has_own_message("nl", "ok"));
+        assert!(holder.has_own_message("nl", "quit"));
+        // Neither the other files nor `base.ftl` with the greeting match
+        assert!(!holder.get_bundle("nl").has_message("ban"));
+        assert!(!holder.get_bundle("nl").has_message("greeting"));
+    }
+
+    #[test]
+    fn config_and_io_errors_are_other_loading_errors() {
+        let base = directory("error-variants");
+
+        let config = load_resources_from_folder(base.join("nl"), Some("[ui"))
+            .unwrap_err()
+            .downcast::<LoadError>()
+            .unwrap();
+        assert!(matches!(config, LoadError::Config(_)));
+        assert!(matches!(
+            config.variants().as_slice(),
+            [LocalizationLoadingError::Other(message)] if *message == config.to_string()
+        ));
+
+        // A file where the directory should be can't be walked through
+        let io = load_resources_from_folder(base.join("nl").join("base.ftl"), None)
+            .unwrap_err()
+            .downcast::<LoadError>()
+            .unwrap();
+        assert!(matches!(io, LoadError::Io { .. }));
+        assert!(matches!(
+            io.variants().as_slice(),
+            [LocalizationLoadingError::Other(message)] if *message == io.to_string()
+        ));
+    }
 }
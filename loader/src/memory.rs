@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use crate::{
+    parse_resource, sort_by_priority, split_priority, LoadError, Normalization, Resource,
+    ResourceSource, FILE_EXTENSION,
+};
+
+/// Resources kept in memory rather than loaded from files, mainly for tests.
+/// Files are given by their name without extension (`greetings` for `greetings.ftl`), a priority prefix works like it does for files
+#[derive(Clone, Default)]
+pub struct InMemorySource {
+    /// Content of the default files by name
+    pub default: HashMap<String, String>,
+    /// Content of the files of each language, by language and then by file name
+    pub languages: HashMap<String, HashMap<String, String>>,
+}
+
+impl InMemorySource {
+    pub fn builder() -> InMemorySourceBuilder {
+        InMemorySourceBuilder::default()
+    }
+
+    fn parse_partial(
+        language: &str,
+        files: &HashMap<String, String>,
+    ) -> (Vec<Resource>, Vec<LoadError>) {
+        let mut files: Vec<(Option<u32>, String, String, &String)> = files
+            .iter()
+            .map(|(name, content)| {
+                let (priority, stem) = split_priority(name);
+                (
+                    priority,
+                    stem.to_string(),
+                    format!("{name}{FILE_EXTENSION}"),
+                    content,
+                )
+            })
+            .collect();
+        sort_by_priority(&mut files);
+
+        let mut parsed = Vec::new();
+        let mut errors = Vec::new();
+        for (priority, stem, file_name, content) in files {
+            match parse_resource(
+                &format!("{language}/{file_name}"),
+                priority,
+                stem,
+                file_name,
+                content.clone(),
+                Normalization::None,
+            ) {
+                Ok(resource) => parsed.push(resource),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (parsed, errors)
+    }
+
+    fn parse(language: &str, files: &HashMap<String, String>) -> Result<Vec<Resource>, LoadError> {
+        let (resources, errors) = Self::parse_partial(language, files);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(resources),
+        }
+    }
+
+    fn files(&self, language: &str) -> Result<&HashMap<String, String>, LoadError> {
+        self.languages.get(language).ok_or_else(|| {
+            LoadError::Config(format!("There are no in memory resources for {language}"))
+        })
+    }
+}
+
+impl ResourceSource for InMemorySource {
+    fn default_resources(&self) -> Result<Vec<Resource>, LoadError> {
+        Self::parse("default", &self.default)
+    }
+
+    fn languages(&self) -> Result<Vec<String>, LoadError> {
+        Ok(self.languages.keys().cloned().collect())
+    }
+
+    fn language_resources(&self, language: &str) -> Result<Vec<Resource>, LoadError> {
+        Self::parse(language, self.files(language)?)
+    }
+
+    fn default_resources_partial(&self) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        Ok(Self::parse_partial("default", &self.default))
+    }
+
+    fn language_resources_partial(
+        &self,
+        language: &str,
+    ) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        Ok(Self::parse_partial(language, self.files(language)?))
+    }
+}
+
+/// Builder to set up an [`InMemorySource`] one language at a time
+#[derive(Default)]
+pub struct InMemorySourceBuilder {
+    source: InMemorySource,
+}
+
+impl InMemorySourceBuilder {
+    /// Add default files as (name, content) pairs
+    pub fn default_files<'s>(
+        mut self,
+        files: impl IntoIterator<Item = (&'s str, &'s str)>,
+    ) -> Self {
+        self.source.default.extend(
+            files
+                .into_iter()
+                .map(|(name, content)| (name.to_string(), content.to_string())),
+        );
+        self
+    }
+
+    /// Add files for a language as (name, content) pairs
+    pub fn language<'s>(
+        mut self,
+        language: &str,
+        files: impl IntoIterator<Item = (&'s str, &'s str)>,
+    ) -> Self {
+        self.source
+            .languages
+            .entry(language.to_string())
+            .or_default()
+            .extend(
+                files
+                    .into_iter()
+                    .map(|(name, content)| (name.to_string(), content.to_string())),
+            );
+        self
+    }
+
+    pub fn build(self) -> InMemorySource {
+        self.source
+    }
+}
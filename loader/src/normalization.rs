@@ -0,0 +1,30 @@
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization form to apply to localization files before parsing them.
+/// Different editors and operating systems can store the same text in different forms, normalizing makes them compare equal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Leave the content as is
+    #[default]
+    None,
+    /// Canonical composition
+    Nfc,
+    /// Canonical decomposition
+    Nfd,
+    /// Compatibility composition
+    Nfkc,
+    /// Compatibility decomposition
+    Nfkd,
+}
+
+impl Normalization {
+    pub fn apply(self, text: String) -> String {
+        match self {
+            Normalization::None => text,
+            Normalization::Nfc => text.nfc().collect(),
+            Normalization::Nfd => text.nfd().collect(),
+            Normalization::Nfkc => text.nfkc().collect(),
+            Normalization::Nfkd => text.nfkd().collect(),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::{Display, Formatter};
+
+use fluent_syntax::parser::{ErrorKind, ParserError};
+use tracing::error;
+
+/// Where a parse error is in a file, for showing it to whoever has to fix it.
+/// The `Display` impl gives the format used in `LoadError::Parse`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseErrorDisplay {
+    /// Line of the error, starting at 1
+    pub line: usize,
+    /// Byte offset of the error within the line
+    pub column: usize,
+    pub kind: ErrorKind,
+    /// The line the error is on, without its line ending
+    pub line_text: String,
+}
+
+impl Display for ParseErrorDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}:{}\n    {}",
+            self.kind, self.line, self.column, self.line_text
+        )
+    }
+}
+
+/// Locate a parse error in the content that was parsed
+pub fn format_parse_error(content: &str, err: &ParserError) -> ParseErrorDisplay {
+    // Keep the line endings so the offsets add up to positions in the content
+    let mut line_start = 0;
+    let mut line = "";
+    let mut line_count = 0;
+
+    for l in content.split_inclusive('\n') {
+        line = l;
+        line_count += 1;
+
+        // are we there yet?
+        let next_start = line_start + l.len();
+        if next_start > err.pos.start {
+            break;
+        }
+
+        // Errors at the very end of the file (like an unclosed placeable) are shown at the end of the last line
+        if next_start < content.len() {
+            line_start = next_start;
+        }
+    }
+
+    if err.pos.start > content.len() {
+        error!(
+            "Somehow fluent-rs reported a error that is past the end of the file? File is {} bytes long, looking for {}",
+            content.len(),
+            err.pos.start
+        );
+    }
+
+    let line_text = line.trim_end_matches(['\r', '\n']);
+    ParseErrorDisplay {
+        line: line_count,
+        column: err
+            .pos
+            .start
+            .saturating_sub(line_start)
+            .min(line_text.len()),
+        kind: err.kind.clone(),
+        line_text: line_text.to_string(),
+    }
+}
+
+/// Parse errors as JSON diagnostics for editor integrations, an array of `{"file", "line", "col", "end_col", "message"}` objects.
+/// Lines start at 1, columns are byte offsets within the line like [`ParseErrorDisplay::column`] and errors spanning lines end at the end of the first one
+#[cfg(feature = "json")]
+pub fn parse_error_to_json(file: &str, content: &str, errors: &[ParserError]) -> serde_json::Value {
+    errors
+        .iter()
+        .map(|err| {
+            let display = format_parse_error(content, err);
+            let end_col = (display.column + err.pos.end.saturating_sub(err.pos.start))
+                .min(display.line_text.len());
+            serde_json::json!({
+                "file": file,
+                "line": display.line,
+                "col": display.column,
+                "end_col": end_col,
+                "message": display.kind.to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use fluent_bundle::FluentResource;
+use fluent_syntax::{
+    ast::{Entry, Expression, InlineExpression, Pattern, PatternElement},
+    parser, serializer,
+};
+
+use unic_langid::LanguageIdentifier;
+
+use crate::{LoadError, LocalizationHolder, Resource, ResourceSource};
+
+/// Name of the generated pseudolocalization language
+pub const PSEUDO_LANGUAGE: &str = "pseudo";
+
+// Same accented alphabet fluent-pseudo uses, still readable but obviously not the original text
+const ACCENTED_LOWERCASE: [char; 26] = [
+    'ȧ', 'ƀ', 'ƈ', 'ḓ', 'ḗ', 'ƒ', 'ɠ', 'ħ', 'ī', 'ĵ', 'ķ', 'ŀ', 'ḿ', 'ƞ', 'ǿ', 'ƥ', 'ɋ', 'ř', 'ş',
+    'ŧ', 'ŭ', 'ṽ', 'ẇ', 'ẋ', 'ẏ', 'ẑ',
+];
+const ACCENTED_UPPERCASE: [char; 26] = [
+    'Ȧ', 'Ɓ', 'Ƈ', 'Ḓ', 'Ḗ', 'Ƒ', 'Ɠ', 'Ħ', 'Ī', 'Ĵ', 'Ķ', 'Ŀ', 'Ḿ', 'Ƞ', 'Ǿ', 'Ƥ', 'Ɋ', 'Ř', 'Ş',
+    'Ŧ', 'Ŭ', 'Ṽ', 'Ẇ', 'Ẋ', 'Ẏ', 'Ẑ',
+];
+
+/// Wraps another source and adds a [`PSEUDO_LANGUAGE`] language to it, generated from the default resources.
+/// Every message is wrapped in brackets and has its letters accented (`Hello` becomes `[Ħḗŀŀǿ]`),
+/// making hardcoded strings and truncated layouts stand out without needing any translations
+pub struct PseudolocalizationSource<S> {
+    inner: S,
+}
+
+impl<S: ResourceSource> PseudolocalizationSource<S> {
+    pub fn new(inner: S) -> Self {
+        PseudolocalizationSource { inner }
+    }
+}
+
+impl<S: ResourceSource> ResourceSource for PseudolocalizationSource<S> {
+    fn default_resources(&self) -> Result<Vec<Resource>, LoadError> {
+        self.inner.default_resources()
+    }
+
+    fn languages(&self) -> Result<Vec<String>, LoadError> {
+        let mut languages = self.inner.languages()?;
+        if !languages.iter().any(|language| language == PSEUDO_LANGUAGE) {
+            languages.push(PSEUDO_LANGUAGE.to_string());
+        }
+        Ok(languages)
+    }
+
+    fn language_resources(&self, language: &str) -> Result<Vec<Resource>, LoadError> {
+        if language != PSEUDO_LANGUAGE {
+            return self.inner.language_resources(language);
+        }
+
+        self.inner
+            .default_resources()?
+            .iter()
+            .map(pseudolocalize_resource)
+            .collect()
+    }
+
+    fn default_resources_partial(&self) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        self.inner.default_resources_partial()
+    }
+
+    fn language_resources_partial(
+        &self,
+        language: &str,
+    ) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        if language != PSEUDO_LANGUAGE {
+            return self.inner.language_resources_partial(language);
+        }
+
+        // Problems with the defaults themselves are already reported for those, don't report them a second time here
+        let (defaults, _) = self.inner.default_resources_partial()?;
+        let mut resources = Vec::new();
+        let mut errors = Vec::new();
+        for resource in &defaults {
+            match pseudolocalize_resource(resource) {
+                Ok(resource) => resources.push(resource),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        Ok((resources, errors))
+    }
+}
+
+impl LocalizationHolder {
+    /// Add a language generated from the default resources by transforming their text, like a pseudo locale (`qps-ploc`) for screenshot testing.
+    /// Only text is transformed, placeables are left intact so variables and references keep working. Replaces the language if it was loaded
+    pub fn with_pseudo_locale(
+        &self,
+        code: &str,
+        transform: impl Fn(&str) -> String,
+    ) -> Result<(), LoadError> {
+        let identifier = code.parse::<LanguageIdentifier>().map_err(|e| {
+            LoadError::Config(format!(
+                "Can't add pseudo locale {code}, it is not a valid language identifier: {e}"
+            ))
+        })?;
+        let resources = self
+            .current()
+            .defaults
+            .iter()
+            .map(|resource| {
+                Ok(Resource {
+                    resource: Arc::new(transform_resource(resource, &transform, false)?),
+                    ..resource.clone()
+                })
+            })
+            .collect::<Result<Vec<Resource>, LoadError>>()?;
+
+        self.replace_language(code, identifier, resources)
+    }
+}
+
+fn pseudolocalize_resource(resource: &Resource) -> Result<Resource, LoadError> {
+    let transformed = transform_resource(resource, &pseudolocalize, true)?;
+    Ok(Resource {
+        resource: Arc::new(transformed),
+        ..resource.clone()
+    })
+}
+
+/// Accent every ascii letter in the text, `Hello` becomes `Ħḗŀŀǿ`
+pub fn pseudolocalize(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'a'..='z' => ACCENTED_LOWERCASE[c as usize - 'a' as usize],
+            'A'..='Z' => ACCENTED_UPPERCASE[c as usize - 'A' as usize],
+            _ => c,
+        })
+        .collect()
+}
+
+/// Apply a transformation to all text of a resource, placeables are left intact so variables and references keep working.
+/// With `bracket` set the value and attributes of every message are also wrapped in `[` `]`
+pub(crate) fn transform_resource(
+    resource: &Resource,
+    transform: &dyn Fn(&str) -> String,
+    bracket: bool,
+) -> Result<FluentResource, LoadError> {
+    // FluentResource borrows its AST from the source, so reparse into an owned one we can modify and serialize again
+    let mut ast = match parser::parse(resource.resource.source().to_string()) {
+        Ok(ast) => ast,
+        Err((ast, _)) => ast,
+    };
+
+    for entry in &mut ast.body {
+        match entry {
+            Entry::Message(message) => {
+                for pattern in message.value.iter_mut().chain(
+                    message
+                        .attributes
+                        .iter_mut()
+                        .map(|attribute| &mut attribute.value),
+                ) {
+                    transform_pattern(pattern, transform);
+                    if bracket {
+                        // Added as literals, a bracket at the start of a continuation line would be parsed as a variant
+                        pattern.elements.insert(0, string_literal("["));
+                        pattern.elements.push(string_literal("]"));
+                    }
+                }
+            }
+            Entry::Term(term) => {
+                transform_pattern(&mut term.value, transform);
+                for attribute in &mut term.attributes {
+                    transform_pattern(&mut attribute.value, transform);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let source = serializer::serialize(&ast);
+    FluentResource::try_new(source.clone()).map_err(|(_, error_list)| {
+        LoadError::parse(resource.file_name.clone(), &source, error_list)
+    })
+}
+
+fn transform_pattern(pattern: &mut Pattern<String>, transform: &dyn Fn(&str) -> String) {
+    for element in &mut pattern.elements {
+        match element {
+            PatternElement::TextElement { value } => *value = transform(value),
+            PatternElement::Placeable { expression } => transform_expression(expression, transform),
+        }
+    }
+}
+
+fn transform_expression(expression: &mut Expression<String>, transform: &dyn Fn(&str) -> String) {
+    match expression {
+        Expression::Select { variants, .. } => {
+            for variant in variants {
+                transform_pattern(&mut variant.value, transform);
+            }
+        }
+        Expression::Inline(InlineExpression::Placeable { expression }) => {
+            transform_expression(expression, transform)
+        }
+        Expression::Inline(_) => (),
+    }
+}
+
+fn string_literal(value: &str) -> PatternElement<String> {
+    PatternElement::Placeable {
+        expression: Expression::Inline(InlineExpression::StringLiteral {
+            value: value.to_string(),
+        }),
+    }
+}
@@ -0,0 +1,85 @@
+use std::borrow::Cow;
+
+use fluent_bundle::{FluentArgs, FluentError, FluentValue};
+
+use crate::{LocalizationHolder, LocalizeError, TextDirection};
+
+/// A holder with the language filled in, see [`LocalizationHolder::with_language_override`].
+/// The methods are the same as the ones on the holder, without the language
+#[derive(Clone, Copy)]
+pub struct ScopedLocalizer<'h> {
+    holder: &'h LocalizationHolder,
+    language: &'h str,
+}
+
+impl LocalizationHolder {
+    /// Localize in a specific language for a while (like a notification in the language of its recipient) without passing it to every call.
+    /// This only borrows the holder, nothing about the holder itself changes
+    pub fn with_language_override<'h>(&'h self, language: &'h str) -> ScopedLocalizer<'h> {
+        ScopedLocalizer {
+            holder: self,
+            language,
+        }
+    }
+}
+
+impl<'h> ScopedLocalizer<'h> {
+    pub fn language(&self) -> &'h str {
+        self.language
+    }
+
+    pub fn holder(&self) -> &'h LocalizationHolder {
+        self.holder
+    }
+
+    /// See [`LocalizationHolder::format_message`]
+    pub fn format_message(
+        &self,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+        errors: &mut Vec<FluentError>,
+    ) -> Option<Cow<'h, str>> {
+        self.holder
+            .format_message(self.language, name, arguments, errors)
+    }
+
+    /// See [`LocalizationHolder::try_format_message`]
+    pub fn try_format_message(
+        &self,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+    ) -> Result<Cow<'h, str>, LocalizeError> {
+        self.holder
+            .try_format_message(self.language, name, arguments)
+    }
+
+    /// See [`LocalizationHolder::format_term`]
+    pub fn format_term(
+        &self,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+        errors: &mut Vec<FluentError>,
+    ) -> Option<Cow<'h, str>> {
+        self.holder
+            .format_term(self.language, name, arguments, errors)
+    }
+
+    /// See [`LocalizationHolder::message_value`]
+    pub fn message_value(
+        &self,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+    ) -> Option<FluentValue<'static>> {
+        self.holder.message_value(self.language, name, arguments)
+    }
+
+    /// See [`LocalizationHolder::has_own_message`]
+    pub fn has_own_message(&self, name: &str) -> bool {
+        self.holder.has_own_message(self.language, name)
+    }
+
+    /// See [`LocalizationHolder::text_direction`]
+    pub fn text_direction(&self) -> TextDirection {
+        self.holder.text_direction(self.language)
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fluent_syntax::ast::{
+    Entry, Expression, InlineExpression, Pattern, PatternElement, VariantKey,
+};
+
+use crate::LocalizationHolder;
+
+// CLDR plural categories, which languages are expected to differ in. `other` is left out, every select has that one
+const PLURAL_CATEGORIES: [&str; 5] = ["zero", "one", "two", "few", "many"];
+
+/// A select in a translation that doesn't offer the same variants as the select on the same variable in the default language
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectDiscrepancy {
+    /// The message, or `message.attribute` for a select in an attribute
+    pub key: String,
+    pub language: String,
+    /// The variable that is selected on
+    pub variable: String,
+    /// Variant keys the translation has that the default language doesn't
+    pub added: Vec<String>,
+    /// Variant keys of the default language the translation doesn't have
+    pub missing: Vec<String>,
+}
+
+impl LocalizationHolder {
+    /// Compare the variants of the selects in every translated message with the ones in the default language, to catch translators
+    /// dropping a variant or inventing one the selector never produces. Plural categories and numbers are left out of the comparison
+    /// of plural selects, as those differ between languages. Selects on a variable the default language doesn't select on are not compared
+    pub fn select_discrepancies(&self) -> Vec<SelectDiscrepancy> {
+        let default_language = self.default_language();
+        let default_selects = message_selects(self, &default_language);
+
+        let mut discrepancies = Vec::new();
+        for language in self.languages() {
+            if language == default_language || self.is_debug_language(&language) {
+                continue;
+            }
+
+            for (key, selects) in message_selects(self, &language) {
+                if !self.has_own_message(&language, &key) {
+                    continue;
+                }
+                let Some(expected_selects) = default_selects.get(&key) else {
+                    continue;
+                };
+
+                for (variable, variants) in selects {
+                    let Some(expected) = expected_selects.get(&variable) else {
+                        continue;
+                    };
+                    let added: Vec<String> = variants.difference(expected).cloned().collect();
+                    let missing: Vec<String> = expected.difference(&variants).cloned().collect();
+                    if !added.is_empty() || !missing.is_empty() {
+                        discrepancies.push(SelectDiscrepancy {
+                            key: key.clone(),
+                            language: language.clone(),
+                            variable,
+                            added,
+                            missing,
+                        });
+                    }
+                }
+            }
+        }
+
+        discrepancies.sort_unstable_by(|a, b| {
+            (&a.key, &a.language, &a.variable).cmp(&(&b.key, &b.language, &b.variable))
+        });
+        discrepancies
+    }
+}
+
+/// The variant keys per selected variable of every message and attribute of a language, keyed like they are localized
+fn message_selects(
+    holder: &LocalizationHolder,
+    language: &str,
+) -> BTreeMap<String, BTreeMap<String, BTreeSet<String>>> {
+    let mut messages = BTreeMap::new();
+    for entry in holder.current().effective_entries(language).into_values() {
+        let Entry::Message(message) = entry else {
+            continue;
+        };
+
+        let patterns = message
+            .value
+            .iter()
+            .map(|value| (message.id.name.to_string(), value))
+            .chain(message.attributes.iter().map(|attribute| {
+                (
+                    format!("{}.{}", message.id.name, attribute.id.name),
+                    &attribute.value,
+                )
+            }));
+        for (key, pattern) in patterns {
+            let mut selects = BTreeMap::new();
+            pattern_selects(pattern, &mut selects);
+            if !selects.is_empty() {
+                messages.insert(key, selects);
+            }
+        }
+    }
+
+    messages
+}
+
+fn pattern_selects(pattern: &Pattern<&str>, selects: &mut BTreeMap<String, BTreeSet<String>>) {
+    for element in &pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            expression_selects(expression, selects);
+        }
+    }
+}
+
+fn expression_selects(
+    expression: &Expression<&str>,
+    selects: &mut BTreeMap<String, BTreeSet<String>>,
+) {
+    match expression {
+        Expression::Select { selector, variants } => {
+            // Selects on a function of a variable (`NUMBER($count)`) count as a select on the variable
+            let variable = match selector {
+                InlineExpression::VariableReference { id } => Some(id.name),
+                InlineExpression::FunctionReference { arguments, .. } => {
+                    match arguments.positional.first() {
+                        Some(InlineExpression::VariableReference { id }) => Some(id.name),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            if let Some(variable) = variable {
+                let plural = variants.iter().any(|variant| match variant.key {
+                    VariantKey::NumberLiteral { .. } => true,
+                    VariantKey::Identifier { name } => PLURAL_CATEGORIES.contains(&name),
+                });
+                let keys = variants.iter().filter_map(|variant| match variant.key {
+                    VariantKey::NumberLiteral { .. } if plural => None,
+                    VariantKey::Identifier { name }
+                        if plural && PLURAL_CATEGORIES.contains(&name) =>
+                    {
+                        None
+                    }
+                    VariantKey::Identifier { name } | VariantKey::NumberLiteral { value: name } => {
+                        Some(name.to_string())
+                    }
+                });
+                selects
+                    .entry(variable.to_string())
+                    .or_default()
+                    .extend(keys);
+            }
+
+            for variant in variants {
+                pattern_selects(&variant.value, selects);
+            }
+        }
+        Expression::Inline(InlineExpression::Placeable { expression }) => {
+            expression_selects(expression, selects)
+        }
+        Expression::Inline(_) => (),
+    }
+}
@@ -0,0 +1,183 @@
+use std::{fs, path::PathBuf};
+
+use glob::Pattern;
+use tracing::trace;
+
+use crate::{
+    load_resources, load_resources_partial, read_dir, LoadError, Normalization, Resource,
+    DEFAULT_DIR, DEFAULT_MAX_FILE_SIZE,
+};
+
+/// Somewhere localization resources can be loaded from
+pub trait ResourceSource {
+    /// The resources every language is layered on top of, these are also the ones the bindings are generated from
+    fn default_resources(&self) -> Result<Vec<Resource>, LoadError>;
+
+    /// Names of all the languages this source has resources for
+    fn languages(&self) -> Result<Vec<String>, LoadError>;
+
+    /// The resources of a single language, as named by `languages`
+    fn language_resources(&self, language: &str) -> Result<Vec<Resource>, LoadError>;
+
+    /// Like `default_resources`, but with the problems of individual resources returned separately from the ones that did load.
+    /// Sources that can't tell resources apart can rely on the default, which reports the whole set as broken
+    fn default_resources_partial(&self) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        self.default_resources()
+            .map(|resources| (resources, Vec::new()))
+    }
+
+    /// Like `language_resources`, but with the problems of individual resources returned separately from the ones that did load
+    fn language_resources_partial(
+        &self,
+        language: &str,
+    ) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        self.language_resources(language)
+            .map(|resources| (resources, Vec::new()))
+    }
+}
+
+/// Loads resources from a localizations directory, with a subdirectory per language and a `default` directory
+#[derive(Clone)]
+pub struct DirectorySource {
+    pub base_path: PathBuf,
+    /// Unicode normalization applied to every file before parsing it
+    pub normalization: Normalization,
+    /// Only load the files whose name matches this glob pattern, all `.ftl` files are loaded without one
+    pub file_pattern: Option<Pattern>,
+    /// Only load these languages, all language directories are loaded without a list
+    pub languages: Option<Vec<String>>,
+    /// Files larger than this many bytes are reported instead of loaded
+    pub max_file_size: u64,
+}
+
+impl DirectorySource {
+    pub fn new(base_path: PathBuf) -> Self {
+        DirectorySource {
+            base_path,
+            normalization: Normalization::None,
+            file_pattern: None,
+            languages: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn with_file_pattern(mut self, file_pattern: Pattern) -> Self {
+        self.file_pattern = Some(file_pattern);
+        self
+    }
+
+    pub fn with_languages(mut self, languages: Vec<String>) -> Self {
+        self.languages = Some(languages);
+        self
+    }
+
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Load the resources of a language (or the default ones with [`DEFAULT_DIR`]), reusing the ones in `cached` for files that weren't modified
+    pub(crate) fn load_cached(
+        &self,
+        language: &str,
+        cached: &[Resource],
+    ) -> Result<Vec<Resource>, LoadError> {
+        load_resources(
+            &self.base_path.join(language),
+            self.normalization,
+            self.file_pattern.as_ref(),
+            self.max_file_size,
+            cached,
+        )
+    }
+}
+
+impl ResourceSource for DirectorySource {
+    fn default_resources(&self) -> Result<Vec<Resource>, LoadError> {
+        load_resources(
+            &self.base_path.join(DEFAULT_DIR),
+            self.normalization,
+            self.file_pattern.as_ref(),
+            self.max_file_size,
+            &[],
+        )
+    }
+
+    fn languages(&self) -> Result<Vec<String>, LoadError> {
+        let mut languages = Vec::new();
+
+        for result in read_dir(&self.base_path, "localizations base dir")? {
+            let item_handle = result.map_err(|e| {
+                LoadError::io(
+                    "Failed to get a handle when walking through the localizations directory"
+                        .to_string(),
+                    e,
+                )
+            })?;
+            //Store the file name in a var separately before the actual name is stored so it isn't dropped early
+            let underlying_name = item_handle.file_name();
+            let lang_name = underlying_name.to_string_lossy();
+
+            // Metadata rather than the file type of the entry, so symlinked language directories are followed
+            let meta = fs::metadata(item_handle.path()).map_err(|e| {
+                LoadError::io(format!("Failed to get item metadata for {lang_name}"), e)
+            })?;
+
+            if !meta.is_dir() {
+                trace!("Skipping {lang_name} because it is not a directory");
+                continue;
+            }
+
+            if self
+                .languages
+                .as_ref()
+                .is_some_and(|languages| !languages.iter().any(|language| *language == lang_name))
+            {
+                trace!("Skipping {lang_name} because it is not one of the languages to load");
+                continue;
+            }
+
+            languages.push(lang_name.to_string());
+        }
+
+        Ok(languages)
+    }
+
+    fn language_resources(&self, language: &str) -> Result<Vec<Resource>, LoadError> {
+        load_resources(
+            &self.base_path.join(language),
+            self.normalization,
+            self.file_pattern.as_ref(),
+            self.max_file_size,
+            &[],
+        )
+    }
+
+    fn default_resources_partial(&self) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        load_resources_partial(
+            &self.base_path.join(DEFAULT_DIR),
+            self.normalization,
+            self.file_pattern.as_ref(),
+            self.max_file_size,
+            &[],
+        )
+    }
+
+    fn language_resources_partial(
+        &self,
+        language: &str,
+    ) -> Result<(Vec<Resource>, Vec<LoadError>), LoadError> {
+        load_resources_partial(
+            &self.base_path.join(language),
+            self.normalization,
+            self.file_pattern.as_ref(),
+            self.max_file_size,
+            &[],
+        )
+    }
+}
@@ -0,0 +1,48 @@
+use std::{env, path::Path};
+
+use anyhow::Result;
+use tracing::debug;
+use unic_langid::LanguageIdentifier;
+
+use crate::{base_path, default_language_from_env, DirectorySource, LoadError, ResourceSource};
+
+/// Get the default language like [`crate::get_default_language`], but when `DEFAULT_LANG` is not set use the language of the operating system
+/// if the localizations directory has it. Falls back to `en_US` if it doesn't
+pub fn detect_default_language() -> Result<LanguageIdentifier> {
+    Ok(detected_default_language(&base_path())?)
+}
+
+pub(crate) fn detected_default_language(
+    base_path: &Path,
+) -> std::result::Result<LanguageIdentifier, LoadError> {
+    if env::var_os("DEFAULT_LANG").is_none() {
+        if let Some(language) = system_language(base_path) {
+            return Ok(language);
+        }
+    }
+
+    default_language_from_env()
+}
+
+/// The language of the operating system, as the name of the language directory it matches.
+/// An exact match is preferred over one for the same language in another region
+fn system_language(base_path: &Path) -> Option<LanguageIdentifier> {
+    let system: LanguageIdentifier = sys_locale::get_locale()?.parse().ok()?;
+    let available: Vec<LanguageIdentifier> = DirectorySource::new(base_path.to_path_buf())
+        .languages()
+        .ok()?
+        .iter()
+        .filter_map(|language| language.parse().ok())
+        .collect();
+
+    let found = available
+        .iter()
+        .find(|language| **language == system)
+        .or_else(|| {
+            available
+                .iter()
+                .find(|language| language.language == system.language)
+        })?;
+    debug!("Using the system language {system} as {found}");
+    Some(found.clone())
+}
@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+
+use fluent_bundle::{FluentArgs, FluentValue};
+use fluent_syntax::ast::{Expression, InlineExpression, PatternElement};
+
+use crate::{find_pattern, LocalizationHolder};
+
+/// Text that was already formatted by the caller (like a number or date formatted with your own ICU setup),
+/// passed to fluent as is so it doesn't get formatted again.
+///
+/// Fluent can't select on or apply `NUMBER()` to text, so don't use this for variables the message uses as numbers:
+/// `"1"` passed to a plural select always shows the default variant. The generated helpers require a number for those.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preformatted<'a>(pub Cow<'a, str>);
+
+impl<'a> Preformatted<'a> {
+    pub fn new(text: impl Into<Cow<'a, str>>) -> Self {
+        Preformatted(text.into())
+    }
+}
+
+impl<'a> From<Preformatted<'a>> for FluentValue<'a> {
+    fn from(value: Preformatted<'a>) -> Self {
+        FluentValue::String(value.0)
+    }
+}
+
+impl LocalizationHolder {
+    /// The value of a message that consists of a single element, like `{ $count }`, `{ NUMBER($amount, minimumFractionDigits: 2) }`,
+    /// a number literal or plain text, without formatting it into a string first.
+    /// Returns `None` for anything else (like a message with text around a placeable), those need to be formatted with [`LocalizationHolder::format_message`].
+    /// Custom functions can't be resolved this way, `NUMBER` is applied by merging its options into the number
+    pub fn message_value(
+        &self,
+        language: &str,
+        name: &str,
+        arguments: Option<&FluentArgs>,
+    ) -> Option<FluentValue<'static>> {
+        if self.is_debug_language(language) {
+            return Some(FluentValue::String(format!("[{name}]").into()));
+        }
+
+        let bundle = self.get_bundle(language);
+        let pattern = find_pattern(&bundle, name).ok()?;
+        let [element] = pattern.elements.as_slice() else {
+            return None;
+        };
+
+        match element {
+            PatternElement::TextElement { value } => {
+                Some(FluentValue::String(value.to_string().into()))
+            }
+            PatternElement::Placeable {
+                expression: Expression::Inline(expression),
+            } => self.inline_value(expression, arguments),
+            PatternElement::Placeable { .. } => None,
+        }
+    }
+
+    fn inline_value(
+        &self,
+        expression: &InlineExpression<&str>,
+        arguments: Option<&FluentArgs>,
+    ) -> Option<FluentValue<'static>> {
+        match expression {
+            InlineExpression::NumberLiteral { value } => {
+                Some(FluentValue::try_number(value).into_owned())
+            }
+            InlineExpression::StringLiteral { value } => {
+                Some(FluentValue::String(value.to_string().into()))
+            }
+            InlineExpression::VariableReference { id } => arguments
+                .and_then(|arguments| arguments.get(id.name))
+                .map(FluentValue::into_owned)
+                .or_else(|| self.global_args().get(id.name).map(FluentValue::into_owned)),
+            InlineExpression::FunctionReference {
+                id,
+                arguments: call,
+            } if id.name == "NUMBER" && call.positional.len() == 1 => {
+                let FluentValue::Number(mut number) =
+                    self.inline_value(&call.positional[0], arguments)?
+                else {
+                    return None;
+                };
+
+                // Options can only be literals, so these resolve without needing the arguments
+                let mut options = FluentArgs::new();
+                for named in &call.named {
+                    options.set(named.name.name, self.inline_value(&named.value, None)?);
+                }
+                number.options.merge(&options);
+
+                Some(FluentValue::Number(number))
+            }
+            InlineExpression::Placeable { expression } => match expression.as_ref() {
+                Expression::Inline(expression) => self.inline_value(expression, arguments),
+                Expression::Select { .. } => None,
+            },
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+
+use crate::Resource;
+
+// Variables an entry uses itself, and the messages it references
+type OwnVariables<'a> = (BTreeSet<&'a str>, Vec<String>);
+
+/// The variables every message needs, including the ones used by the messages it references, the same way the bindings see them.
+/// Keys are like the ones of the bindings: `message.attribute` for attributes and terms prefixed with `-`. Later resources override the
+/// entries of earlier ones. Terms only see the arguments a reference passes them (`{ -brand(case: "upper") }`), so the variables of a
+/// term are never needed by the message referencing it, only the ones used in the values of those arguments are
+pub fn message_variables(resources: &[Resource]) -> BTreeMap<String, BTreeSet<String>> {
+    let mut winners: HashMap<(bool, &str), &Entry<&str>> = HashMap::new();
+    for entry in resources
+        .iter()
+        .flat_map(|resource| resource.resource.entries())
+    {
+        match entry {
+            Entry::Message(message) => winners.insert((false, message.id.name), entry),
+            Entry::Term(term) => winners.insert((true, term.id.name), entry),
+            _ => None,
+        };
+    }
+
+    let mut own: HashMap<String, OwnVariables> = HashMap::new();
+    for entry in winners.into_values() {
+        let (prefix, id, value, attributes) = match entry {
+            Entry::Message(message) => (
+                "",
+                message.id.name,
+                message.value.as_ref(),
+                &message.attributes,
+            ),
+            Entry::Term(term) => ("-", term.id.name, Some(&term.value), &term.attributes),
+            _ => continue,
+        };
+        let patterns = value
+            .map(|value| (format!("{prefix}{id}"), value))
+            .into_iter()
+            .chain(attributes.iter().map(|attribute| {
+                (
+                    format!("{prefix}{id}.{}", attribute.id.name),
+                    &attribute.value,
+                )
+            }));
+        for (key, pattern) in patterns {
+            let mut variables = (BTreeSet::new(), Vec::new());
+            pattern_variables(pattern, &mut variables);
+            own.insert(key, variables);
+        }
+    }
+
+    let mut resolved = HashMap::new();
+    let keys: Vec<String> = own.keys().cloned().collect();
+    for key in &keys {
+        resolve(key, &own, &mut resolved, &mut Vec::new());
+    }
+
+    resolved
+        .into_iter()
+        .map(|(key, variables)| {
+            (
+                key,
+                variables
+                    .into_iter()
+                    .map(|variable| variable.to_string())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Variables of an entry combined with the ones of what it references, references forming a cycle don't add anything
+fn resolve<'a>(
+    key: &str,
+    own: &HashMap<String, OwnVariables<'a>>,
+    resolved: &mut HashMap<String, BTreeSet<&'a str>>,
+    stack: &mut Vec<String>,
+) -> BTreeSet<&'a str> {
+    if let Some(variables) = resolved.get(key) {
+        return variables.clone();
+    }
+    let Some((variables, references)) = own.get(key) else {
+        return BTreeSet::new();
+    };
+    if stack.iter().any(|entry| entry == key) {
+        return BTreeSet::new();
+    }
+
+    stack.push(key.to_string());
+    let mut variables = variables.clone();
+    for reference in references {
+        variables.extend(resolve(reference, own, resolved, stack));
+    }
+    stack.pop();
+
+    resolved.insert(key.to_string(), variables.clone());
+    variables
+}
+
+fn pattern_variables<'a>(pattern: &Pattern<&'a str>, variables: &mut OwnVariables<'a>) {
+    for element in &pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            expression_variables(expression, variables);
+        }
+    }
+}
+
+fn expression_variables<'a>(expression: &Expression<&'a str>, variables: &mut OwnVariables<'a>) {
+    match expression {
+        Expression::Select { selector, variants } => {
+            inline_variables(selector, variables);
+            for variant in variants {
+                pattern_variables(&variant.value, variables);
+            }
+        }
+        Expression::Inline(inline) => inline_variables(inline, variables),
+    }
+}
+
+fn inline_variables<'a>(expression: &InlineExpression<&'a str>, variables: &mut OwnVariables<'a>) {
+    match expression {
+        InlineExpression::VariableReference { id } => {
+            variables.0.insert(id.name);
+        }
+        InlineExpression::MessageReference { id, attribute } => {
+            let key = match attribute {
+                Some(attribute) => format!("{}.{}", id.name, attribute.name),
+                None => id.name.to_string(),
+            };
+            variables.1.push(key);
+        }
+        // Fluent gives terms a scope of their own with only the named arguments in it, the positional ones are ignored by the term
+        // but still resolved in the scope of the message
+        InlineExpression::TermReference { arguments, .. } => {
+            for argument in arguments.iter().flat_map(|arguments| {
+                arguments
+                    .positional
+                    .iter()
+                    .chain(arguments.named.iter().map(|named| &named.value))
+            }) {
+                inline_variables(argument, variables);
+            }
+        }
+        InlineExpression::FunctionReference { arguments, .. } => {
+            for argument in arguments
+                .positional
+                .iter()
+                .chain(arguments.named.iter().map(|named| &named.value))
+            {
+                inline_variables(argument, variables);
+            }
+        }
+        InlineExpression::Placeable { expression } => expression_variables(expression, variables),
+        InlineExpression::StringLiteral { .. } | InlineExpression::NumberLiteral { .. } => (),
+    }
+}
@@ -0,0 +1 @@
+greeting = Hallo
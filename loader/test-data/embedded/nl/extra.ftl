@@ -0,0 +1 @@
+farewell = Tot ziens
@@ -0,0 +1,30 @@
+//! Detecting the system language reads and changes the process environment, so it runs in a test binary of its own
+#![cfg(feature = "sys-locale")]
+
+use std::{env, fs};
+
+use fluent_localization_loader::detect_default_language;
+
+#[test]
+fn default_lang_overrides_the_system_language() {
+    let base = env::temp_dir().join(format!(
+        "fluent-localization-system-locale-{}",
+        std::process::id()
+    ));
+    for language in ["default", "en-US", "nl"] {
+        fs::create_dir_all(base.join(language)).unwrap();
+        fs::write(base.join(language).join("base.ftl"), "greeting = Hello").unwrap();
+    }
+    env::set_var("TRANSLATION_DIR", &base);
+
+    // sys-locale reads the locale from the environment on unix
+    env::set_var("LC_ALL", "nl_NL.UTF-8");
+    env::remove_var("DEFAULT_LANG");
+    let detected = detect_default_language().unwrap();
+    if cfg!(unix) {
+        assert_eq!(detected.to_string(), "nl");
+    }
+
+    env::set_var("DEFAULT_LANG", "en-US");
+    assert_eq!(detect_default_language().unwrap().to_string(), "en-US");
+}